@@ -0,0 +1,96 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use vsrg_utils::enums::quaver::{GameMode, ModIdentifier};
+use vsrg_utils::maps::processors::{DifficultyProcessor, StrainConstants};
+use vsrg_utils::maps::structures::HitObjectInfo;
+use vsrg_utils::maps::QuaverMap;
+
+const MAP_LENGTH_MS: i32 = 10 * 60 * 1000;
+const NOTE_COUNT: usize = 20_000;
+
+// A 10 minute marathon with 20k notes: rows of one to three notes every 60ms on average, with
+// some long notes mixed in.
+fn marathon_map(mode: GameMode, seed: u64) -> QuaverMap {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let key_count = mode.key_count() as i32;
+
+    let mut map = QuaverMap {
+        mode,
+        ..Default::default()
+    };
+
+    let mut time = 1000;
+    while map.hit_objects.len() < NOTE_COUNT {
+        let chord_size = rng.gen_range(1..=3);
+        let mut lanes: Vec<i32> = (1..=key_count).collect();
+
+        for _ in 0..chord_size {
+            let lane = lanes.swap_remove(rng.gen_range(0..lanes.len()));
+            let end_time = if rng.gen::<f32>() < 0.1 {
+                time + rng.gen_range(100..600)
+            } else {
+                0
+            };
+
+            map.hit_objects.push(HitObjectInfo {
+                start_time: time,
+                lane,
+                end_time,
+            });
+        }
+
+        time += rng.gen_range(30..=90);
+    }
+
+    map.hit_objects.truncate(NOTE_COUNT);
+    map.sort();
+
+    assert!(time <= MAP_LENGTH_MS + 1000);
+    map
+}
+
+fn difficulty(c: &mut Criterion) {
+    let mut group = c.benchmark_group("difficulty_marathon");
+    group.sample_size(10);
+
+    for mode in [GameMode::Keys4, GameMode::Keys7] {
+        let map = marathon_map(mode, 0);
+
+        group.bench_with_input(
+            BenchmarkId::new("overall", format!("{:?}", mode)),
+            &map,
+            |b, map| {
+                b.iter(|| {
+                    DifficultyProcessor::new(
+                        map,
+                        StrainConstants::new(),
+                        Some(ModIdentifier::empty()),
+                        None,
+                    )
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("detailed", format!("{:?}", mode)),
+            &map,
+            |b, map| {
+                b.iter(|| {
+                    DifficultyProcessor::new(
+                        map,
+                        StrainConstants::new(),
+                        Some(ModIdentifier::empty()),
+                        Some(true),
+                    )
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, difficulty);
+criterion_main!(benches);
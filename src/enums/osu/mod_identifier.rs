@@ -38,3 +38,29 @@ bitflags! {
         const Mirror = 1 << 30;
     }
 }
+
+impl ModIdentifier {
+    pub fn key_count(&self) -> Option<i32> {
+        if self.contains(ModIdentifier::Key1) {
+            Some(1)
+        } else if self.contains(ModIdentifier::Key2) {
+            Some(2)
+        } else if self.contains(ModIdentifier::Key3) {
+            Some(3)
+        } else if self.contains(ModIdentifier::Key4) {
+            Some(4)
+        } else if self.contains(ModIdentifier::Key5) {
+            Some(5)
+        } else if self.contains(ModIdentifier::Key6) {
+            Some(6)
+        } else if self.contains(ModIdentifier::Key7) {
+            Some(7)
+        } else if self.contains(ModIdentifier::Key8) {
+            Some(8)
+        } else if self.contains(ModIdentifier::Key9) {
+            Some(9)
+        } else {
+            None
+        }
+    }
+}
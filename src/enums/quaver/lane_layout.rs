@@ -0,0 +1,118 @@
+use super::GameMode;
+
+// Lanes are 1-based everywhere, matching `HitObjectInfo.lane`. The scratch lane is always the
+// last lane (5 for 4K+1, 8 for 7K+1), both in maps and in replay frames where lane N is bit N - 1.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LaneLayout {
+    #[default]
+    Keys4,
+    Keys4Scratch,
+    Keys7,
+    Keys7Scratch,
+}
+
+impl LaneLayout {
+    pub fn new(mode: GameMode, has_scratch_key: bool) -> Self {
+        match (mode, has_scratch_key) {
+            (GameMode::Keys4, false) => LaneLayout::Keys4,
+            (GameMode::Keys4, true) => LaneLayout::Keys4Scratch,
+            (GameMode::Keys7, false) => LaneLayout::Keys7,
+            (GameMode::Keys7, true) => LaneLayout::Keys7Scratch,
+        }
+    }
+
+    // osu! has no notion of a scratch lane, so only 8K is read as 7K+1.
+    pub fn from_key_count(key_count: i32) -> Option<Self> {
+        match key_count {
+            4 => Some(LaneLayout::Keys4),
+            7 => Some(LaneLayout::Keys7),
+            8 => Some(LaneLayout::Keys7Scratch),
+            _ => None,
+        }
+    }
+
+    pub fn mode(self) -> GameMode {
+        match self {
+            LaneLayout::Keys4 | LaneLayout::Keys4Scratch => GameMode::Keys4,
+            LaneLayout::Keys7 | LaneLayout::Keys7Scratch => GameMode::Keys7,
+        }
+    }
+
+    pub fn key_count(self) -> i32 {
+        match self {
+            LaneLayout::Keys4 => 4,
+            LaneLayout::Keys4Scratch => 5,
+            LaneLayout::Keys7 => 7,
+            LaneLayout::Keys7Scratch => 8,
+        }
+    }
+
+    pub fn has_scratch_key(self) -> bool {
+        matches!(self, LaneLayout::Keys4Scratch | LaneLayout::Keys7Scratch)
+    }
+
+    pub fn scratch_lane(self) -> Option<i32> {
+        self.has_scratch_key().then(|| self.key_count())
+    }
+
+    pub fn is_scratch_lane(self, lane: i32) -> bool {
+        self.scratch_lane() == Some(lane)
+    }
+
+    pub fn contains_lane(self, lane: i32) -> bool {
+        (1..=self.key_count()).contains(&lane)
+    }
+
+    pub fn lanes(self) -> impl Iterator<Item = i32> {
+        1..=self.key_count()
+    }
+
+    // Mirroring flips the key lanes and leaves the scratch lane where it is.
+    pub fn mirror_lane(self, lane: i32) -> Option<i32> {
+        if !self.contains_lane(lane) {
+            return None;
+        }
+
+        Some(if self.is_scratch_lane(lane) {
+            lane
+        } else {
+            self.mode_key_count() + 1 - lane
+        })
+    }
+
+    fn mode_key_count(self) -> i32 {
+        match self.mode() {
+            GameMode::Keys4 => 4,
+            GameMode::Keys7 => 7,
+        }
+    }
+
+    // osu! puts the scratch lane in the first column.
+    pub fn osu_column_to_lane(self, column: i32) -> Option<i32> {
+        if !self.contains_lane(column) {
+            return None;
+        }
+
+        Some(if !self.has_scratch_key() {
+            column
+        } else if column == 1 {
+            self.key_count()
+        } else {
+            column - 1
+        })
+    }
+
+    pub fn lane_to_osu_column(self, lane: i32) -> Option<i32> {
+        if !self.contains_lane(lane) {
+            return None;
+        }
+
+        Some(if !self.has_scratch_key() {
+            lane
+        } else if self.is_scratch_lane(lane) {
+            1
+        } else {
+            lane + 1
+        })
+    }
+}
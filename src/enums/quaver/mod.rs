@@ -1,11 +1,13 @@
 mod game_mode;
 mod hit_sounds;
+mod lane_layout;
 mod mod_identifier;
 mod qss_pattern_flags;
 mod time_signature;
 
 pub use game_mode::*;
 pub use hit_sounds::*;
+pub use lane_layout::*;
 pub use mod_identifier::*;
 pub use qss_pattern_flags::*;
 pub use time_signature::*;
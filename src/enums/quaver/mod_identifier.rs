@@ -53,6 +53,45 @@ bitflags! {
 }
 
 impl ModIdentifier {
+    // Every rate Quaver supports, from 0.5x to 2.0x. 1.0x has no mod of its own.
+    pub const SPEED_MODS: [ModIdentifier; 31] = [
+        ModIdentifier::Speed05X,
+        ModIdentifier::Speed055X,
+        ModIdentifier::Speed06X,
+        ModIdentifier::Speed065X,
+        ModIdentifier::Speed07X,
+        ModIdentifier::Speed075X,
+        ModIdentifier::Speed08X,
+        ModIdentifier::Speed085X,
+        ModIdentifier::Speed09X,
+        ModIdentifier::Speed095X,
+        ModIdentifier::empty(),
+        ModIdentifier::Speed105X,
+        ModIdentifier::Speed11X,
+        ModIdentifier::Speed115X,
+        ModIdentifier::Speed12X,
+        ModIdentifier::Speed125X,
+        ModIdentifier::Speed13X,
+        ModIdentifier::Speed135X,
+        ModIdentifier::Speed14X,
+        ModIdentifier::Speed145X,
+        ModIdentifier::Speed15X,
+        ModIdentifier::Speed155X,
+        ModIdentifier::Speed16X,
+        ModIdentifier::Speed165X,
+        ModIdentifier::Speed17X,
+        ModIdentifier::Speed175X,
+        ModIdentifier::Speed18X,
+        ModIdentifier::Speed185X,
+        ModIdentifier::Speed19X,
+        ModIdentifier::Speed195X,
+        ModIdentifier::Speed20X,
+    ];
+
+    pub fn rates() -> Vec<f32> {
+        Self::SPEED_MODS.iter().map(|m| m.rate()).collect()
+    }
+
     pub fn rate(&self) -> f32 {
         if self.contains(ModIdentifier::Speed05X) {
             0.5
@@ -1,17 +1,12 @@
 use serde::{de::Error, Deserialize, Serialize};
 
-#[derive(Clone, Copy, Serialize, Debug)]
+#[derive(Clone, Copy, Serialize, Default, Debug)]
 pub enum TimeSignature {
+    #[default]
     Quadruple = 4,
     Triple = 3,
 }
 
-impl Default for TimeSignature {
-    fn default() -> Self {
-        Self::Quadruple
-    }
-}
-
 impl<'de> Deserialize<'de> for TimeSignature {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
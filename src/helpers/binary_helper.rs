@@ -85,16 +85,15 @@ impl BinaryHelper {
         Ok(std::str::from_utf8(vec).unwrap().to_string())
     }
 
-    fn read_7bit_encoded_int(&mut self) -> std::io::Result<u8> {
-        let mut count: u8 = 0;
-        let mut shift: u8 = 0;
-        let b: u8 = self.data[self.pos];
+    fn read_7bit_encoded_int(&mut self) -> std::io::Result<u32> {
+        let mut count: u32 = 0;
+        let mut shift: u32 = 0;
         loop {
-            count |= (b & 0x7F) << shift;
+            let b: u8 = self.read_bytes(1)?[0];
+            count |= ((b & 0x7F) as u32) << shift;
             shift += 7;
-            self.adv(1);
 
-            if (b & 0x80) == 0 {
+            if (b & 0x80) == 0 || shift >= 32 {
                 break;
             };
         }
@@ -120,3 +119,24 @@ impl BinaryHelper {
     read_integer!(read_f32, f32, 4);
     read_integer!(read_f64, f64, 8);
 }
+
+// .NET's BinaryWriter string: the byte length as a 7-bit encoded integer, then UTF-8.
+pub fn write_string(data: &mut Vec<u8>, string: &str) {
+    let mut length = string.len();
+    while length >= 0x80 {
+        data.push((length as u8 & 0x7F) | 0x80);
+        length >>= 7;
+    }
+    data.push(length as u8);
+    data.extend_from_slice(string.as_bytes());
+}
+
+// osu! marks present strings with 0x0b and writes a single 0x00 for empty ones.
+pub fn write_osu_string(data: &mut Vec<u8>, string: &str) {
+    if string.is_empty() {
+        data.push(0x00);
+    } else {
+        data.push(0x0b);
+        write_string(data, string);
+    }
+}
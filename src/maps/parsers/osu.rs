@@ -5,7 +5,7 @@ use std::{
     path::Path,
 };
 
-use crate::enums::quaver::GameMode;
+use crate::enums::quaver::{GameMode, LaneLayout};
 use crate::maps::structures::*;
 use crate::maps::QuaverMap;
 
@@ -111,7 +111,7 @@ impl OsuBeatmap {
 
                 if section.eq("[General]") && line.contains(':') {
                     let key = &line[..line.find(':').unwrap()];
-                    let value = line.split(':').last().unwrap().trim();
+                    let value = line.split(':').next_back().unwrap().trim();
                     match key.trim() {
                         "AudioFilename" => self_.audio_file_name = value.parse().unwrap(),
                         "AudioLeadIn" => self_.audio_lead_in = value.parse().unwrap(),
@@ -136,7 +136,7 @@ impl OsuBeatmap {
 
                 if section.eq("[Editor]") && line.contains(':') {
                     let key = &line[..line.find(':').unwrap()];
-                    let value = line.split(':').last().unwrap().trim();
+                    let value = line.split(':').next_back().unwrap().trim();
 
                     match key.trim() {
                         "Bookmarks" => self_.bookmarks = value.parse().unwrap(),
@@ -150,7 +150,7 @@ impl OsuBeatmap {
 
                 if section.eq("[Metadata]") && line.contains(':') {
                     let key = &line[..line.find(':').unwrap()];
-                    let value = line.split(':').last().unwrap().trim();
+                    let value = line.split(':').next_back().unwrap().trim();
 
                     match key.trim() {
                         "Title" => self_.title = value.parse().unwrap(),
@@ -169,7 +169,7 @@ impl OsuBeatmap {
 
                 if section.eq("[Difficulty]") && line.contains(':') {
                     let key = &line[..line.find(':').unwrap()];
-                    let value = line.split(':').last().unwrap().trim();
+                    let value = line.split(':').next_back().unwrap().trim();
 
                     match key.trim() {
                         "HPDrainRate" => self_.hp_drain_rate = value.parse().unwrap(),
@@ -365,18 +365,14 @@ impl OsuBeatmap {
             }
         }
 
+        let layout = LaneLayout::new(qua.mode, qua.has_scratch_key);
+
         for hit_object in self.hit_objects {
-            let mut key_lane = (hit_object.x as f64 / (512f64 / self.key_count as f64))
+            let column = (hit_object.x as f64 / (512f64 / self.key_count as f64))
                 .clamp(0., (self.key_count - 1) as f64) as i32
                 + 1;
 
-            if qua.has_scratch_key {
-                if key_lane == 1 {
-                    key_lane = self.key_count;
-                } else {
-                    key_lane -= 1
-                };
-            }
+            let key_lane = layout.osu_column_to_lane(column).unwrap_or(column);
 
             if hit_object.type_ == HitObjectType::Circle {
                 qua.hit_objects.push(HitObjectInfo {
@@ -0,0 +1,251 @@
+use anyhow::{anyhow, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{DifficultyProcessor, StrainConstants};
+use crate::maps::QuaverMap;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct FittingEntry {
+    pub path: String,
+    pub rating: f32,
+    #[serde(default = "default_rate")]
+    pub rate: f32,
+}
+
+fn default_rate() -> f32 {
+    1.
+}
+
+#[derive(Clone)]
+pub struct FittingSample {
+    pub map: QuaverMap,
+    pub rate: f32,
+    pub rating: f32,
+}
+
+// Maps with the rating they should end up with, loaded from a YAML list of entries.
+#[derive(Default, Clone)]
+pub struct FittingDataset {
+    pub samples: Vec<FittingSample>,
+}
+
+impl FittingDataset {
+    pub fn new(samples: Vec<FittingSample>) -> Self {
+        Self { samples }
+    }
+
+    // Relative map paths are resolved against `base_path` when given.
+    pub fn from_entries(entries: &[FittingEntry], base_path: Option<&str>) -> Result<Self> {
+        let samples = entries
+            .iter()
+            .map(|entry| {
+                let path = match base_path {
+                    Some(base) => std::path::Path::new(base).join(&entry.path),
+                    None => std::path::PathBuf::from(&entry.path),
+                };
+
+                let file = std::fs::File::open(&path)
+                    .map_err(|e| anyhow!("Failed to open {}: {}", path.display(), e))?;
+
+                Ok(FittingSample {
+                    map: serde_yaml::from_reader(file)?,
+                    rate: entry.rate,
+                    rating: entry.rating,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { samples })
+    }
+
+    pub fn from_path(path: &str) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let entries: Vec<FittingEntry> = serde_yaml::from_reader(file)?;
+        let base_path = std::path::Path::new(path).parent().and_then(|p| p.to_str());
+
+        Self::from_entries(&entries, base_path)
+    }
+
+    // Root mean square error between the calculated and reference ratings.
+    pub fn loss(&self, constants: &StrainConstants) -> f32 {
+        if self.samples.is_empty() {
+            return 0.;
+        }
+
+        let squared_error = self
+            .samples
+            .par_iter()
+            .map(|sample| {
+                let table = DifficultyProcessor::difficulty_table(
+                    &sample.map,
+                    constants.clone(),
+                    Some(&[sample.rate]),
+                    None,
+                );
+                let rating = table.rates[0].overall_difficulty;
+
+                (rating - sample.rating).powi(2) as f64
+            })
+            .sum::<f64>();
+
+        (squared_error / self.samples.len() as f64).sqrt() as f32
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConstantSensitivity {
+    pub name: String,
+    pub initial: f32,
+    pub fitted: f32,
+    // How much the loss drops when only this constant is moved to its fitted value.
+    pub loss_decrease: f32,
+    // Change in loss per 1% change of the constant, measured around the fitted value.
+    pub sensitivity: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct FittingReport {
+    pub constants: StrainConstants,
+    pub initial_loss: f32,
+    pub loss: f32,
+    pub iterations: usize,
+    pub evaluations: usize,
+    pub sensitivities: Vec<ConstantSensitivity>,
+}
+
+impl FittingReport {
+    pub fn to_string(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+}
+
+// Coordinate descent on the relative scale of each constant: every constant is moved up and down
+// by the current step and the first move that lowers the loss is kept. The step halves once a pass
+// doesn't improve anything. Constants that are 0 stay at 0.
+pub struct ConstantsFitter {
+    pub dataset: FittingDataset,
+    pub initial: StrainConstants,
+    // Constants to tune, every one of them when empty.
+    pub parameters: Vec<String>,
+    pub initial_step: f32,
+    pub minimum_step: f32,
+    pub max_iterations: usize,
+}
+
+impl ConstantsFitter {
+    pub fn new(
+        dataset: FittingDataset,
+        initial: Option<StrainConstants>,
+        parameters: Option<Vec<String>>,
+    ) -> Self {
+        Self {
+            dataset,
+            initial: initial.unwrap_or_default(),
+            parameters: parameters.unwrap_or_default(),
+            initial_step: 0.1,
+            minimum_step: 0.005,
+            max_iterations: 50,
+        }
+    }
+
+    pub fn fit(&self) -> Result<FittingReport> {
+        let initial_values = self.initial.parameters()?;
+        let names: Vec<String> = if self.parameters.is_empty() {
+            initial_values
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect()
+        } else {
+            for name in self.parameters.iter() {
+                if !initial_values.iter().any(|(n, _)| n == name) {
+                    return Err(anyhow!("Unknown strain constant: {}", name));
+                }
+            }
+
+            self.parameters.clone()
+        };
+
+        let mut evaluations = 0;
+        let mut loss = |constants: &StrainConstants| {
+            evaluations += 1;
+            self.dataset.loss(constants)
+        };
+
+        let initial_loss = loss(&self.initial);
+        let mut best = self.initial.clone();
+        let mut best_loss = initial_loss;
+        let mut step = self.initial_step;
+        let mut iterations = 0;
+
+        while iterations < self.max_iterations && step >= self.minimum_step {
+            iterations += 1;
+            let mut improved = false;
+
+            for name in names.iter() {
+                let value = parameter(&best, name)?;
+
+                for direction in [1., -1.] {
+                    let candidate = best.with_parameter(name, value * (1. + direction * step))?;
+                    let candidate_loss = loss(&candidate);
+
+                    if candidate_loss < best_loss {
+                        best = candidate;
+                        best_loss = candidate_loss;
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+
+            if !improved {
+                step /= 2.;
+            }
+        }
+
+        const SENSITIVITY_STEP: f32 = 0.01;
+
+        let mut sensitivities = Vec::new();
+        for name in names.iter() {
+            let fitted = parameter(&best, name)?;
+            let up = loss(&best.with_parameter(name, fitted * (1. + SENSITIVITY_STEP))?);
+            let down = loss(&best.with_parameter(name, fitted * (1. - SENSITIVITY_STEP))?);
+
+            let initial = parameter(&self.initial, name)?;
+            let single_change_loss = loss(&self.initial.with_parameter(name, fitted)?);
+
+            sensitivities.push(ConstantSensitivity {
+                name: name.clone(),
+                initial,
+                fitted,
+                loss_decrease: initial_loss - single_change_loss,
+                sensitivity: (up - down) / 2.,
+            });
+        }
+
+        sensitivities.sort_by(|a, b| b.sensitivity.abs().total_cmp(&a.sensitivity.abs()));
+
+        best.name = format!("{} (fitted)", self.initial.name);
+
+        Ok(FittingReport {
+            constants: best,
+            initial_loss,
+            loss: best_loss,
+            iterations,
+            evaluations,
+            sensitivities,
+        })
+    }
+}
+
+fn parameter(constants: &StrainConstants, name: &str) -> Result<f32> {
+    constants
+        .parameters()?
+        .into_iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, value)| value)
+        .ok_or_else(|| anyhow!("Unknown strain constant: {}", name))
+}
@@ -0,0 +1,230 @@
+use anyhow::Result;
+use rayon::prelude::*;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use super::{DifficultyProcessor, StrainConstants};
+use crate::enums::quaver::ModIdentifier;
+use crate::maps::QuaverMap;
+
+pub enum DifficultyBatchInput {
+    // An in-memory map and the md5 of the file it was read from, if known.
+    Map(Box<QuaverMap>, Option<String>),
+    Path(String),
+}
+
+impl From<QuaverMap> for DifficultyBatchInput {
+    fn from(map: QuaverMap) -> Self {
+        DifficultyBatchInput::Map(Box::new(map), None)
+    }
+}
+
+// Entries of `QuaverMapset::maps`, which are keyed by md5.
+impl From<(String, QuaverMap)> for DifficultyBatchInput {
+    fn from((map_md5, map): (String, QuaverMap)) -> Self {
+        DifficultyBatchInput::Map(Box::new(map), Some(map_md5))
+    }
+}
+
+impl From<String> for DifficultyBatchInput {
+    fn from(path: String) -> Self {
+        DifficultyBatchInput::Path(path)
+    }
+}
+
+impl From<&str> for DifficultyBatchInput {
+    fn from(path: &str) -> Self {
+        DifficultyBatchInput::Path(path.to_string())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DifficultyBatchResult {
+    // Position of the map in the input, as results arrive out of order.
+    pub index: usize,
+    // The path, or the map's own file path for in-memory maps.
+    pub source: String,
+    // Md5 of the .qua file, empty if an in-memory map has no md5 and no readable file path.
+    pub map_md5: String,
+    pub version: String,
+    pub overall_difficulty: Result<f32, String>,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct DifficultyBatchProgress {
+    pub completed: usize,
+    pub failed: usize,
+    pub total: Option<usize>,
+}
+
+pub struct DifficultyBatch {
+    pub constants: StrainConstants,
+    pub mods: Option<ModIdentifier>,
+    pub threads: Option<usize>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl DifficultyBatch {
+    pub fn new(
+        constants: StrainConstants,
+        mods: Option<ModIdentifier>,
+        threads: Option<usize>,
+    ) -> Self {
+        Self {
+            constants,
+            mods,
+            threads,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // Shared flag that stops the batch once set; maps already being solved still finish.
+    pub fn cancellation(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    // Solves every map in parallel and hands each result to `on_result` on the calling thread as
+    // soon as it is ready. Returns the final progress.
+    pub fn run<I, F>(&self, inputs: I, mut on_result: F) -> Result<DifficultyBatchProgress>
+    where
+        I: IntoIterator,
+        I::Item: Into<DifficultyBatchInput> + Send,
+        I::IntoIter: Send,
+        F: FnMut(DifficultyBatchResult, DifficultyBatchProgress),
+    {
+        let inputs = inputs.into_iter();
+        let total = match inputs.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(upper),
+            _ => None,
+        };
+
+        let mut pool = rayon::ThreadPoolBuilder::new();
+        if let Some(threads) = self.threads {
+            pool = pool.num_threads(threads);
+        }
+        let pool = pool.build()?;
+
+        let (sender, receiver) = mpsc::channel();
+        let mut progress = DifficultyBatchProgress {
+            total,
+            ..Default::default()
+        };
+
+        std::thread::scope(|scope| {
+            scope.spawn(move || {
+                pool.install(|| {
+                    inputs.enumerate().par_bridge().for_each_with(
+                        sender,
+                        |sender, (index, input)| {
+                            if self.is_cancelled() {
+                                return;
+                            }
+
+                            let _ = sender.send(self.process(index, input.into()));
+                        },
+                    )
+                });
+            });
+
+            for result in receiver {
+                progress.completed += 1;
+                if result.overall_difficulty.is_err() {
+                    progress.failed += 1;
+                }
+
+                on_result(result, progress);
+            }
+        });
+
+        Ok(progress)
+    }
+
+    pub fn collect<I>(&self, inputs: I) -> Result<Vec<DifficultyBatchResult>>
+    where
+        I: IntoIterator,
+        I::Item: Into<DifficultyBatchInput> + Send,
+        I::IntoIter: Send,
+    {
+        let mut results: Vec<DifficultyBatchResult> = Vec::new();
+        self.run(inputs, |result, _| results.push(result))?;
+        results.sort_by_key(|r| r.index);
+
+        Ok(results)
+    }
+
+    fn process(&self, index: usize, input: DifficultyBatchInput) -> DifficultyBatchResult {
+        let start = Instant::now();
+
+        let (source, map_md5, map) = match input {
+            DifficultyBatchInput::Path(path) => {
+                let map = std::fs::read(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|bytes| {
+                        let map_md5 = format!("{:x}", md5::compute(&bytes));
+                        serde_yaml::from_slice::<QuaverMap>(&bytes)
+                            .map(|map| (map_md5, map))
+                            .map_err(|e| e.to_string())
+                    });
+
+                match map {
+                    Ok((map_md5, map)) => (path, map_md5, Ok(Box::new(map))),
+                    Err(e) => (path, String::new(), Err(e)),
+                }
+            }
+            DifficultyBatchInput::Map(map, map_md5) => {
+                let map_md5 = map_md5
+                    .or_else(|| {
+                        std::fs::read(&map.file_path)
+                            .ok()
+                            .map(|bytes| format!("{:x}", md5::compute(bytes)))
+                    })
+                    .unwrap_or_default();
+                (map.file_path.clone(), map_md5, Ok(map))
+            }
+        };
+
+        // `ModIdentifier::None` is stored as -1, which would otherwise set every speed mod.
+        let mods = match self.mods {
+            Some(mods) if mods != ModIdentifier::None => mods,
+            _ => ModIdentifier::empty(),
+        };
+
+        let overall_difficulty = map.and_then(|map| {
+            panic::catch_unwind(AssertUnwindSafe(|| {
+                DifficultyProcessor::new(map.as_ref(), self.constants.clone(), Some(mods), None)
+                    .overall_difficulty
+            }))
+            .map_err(panic_message)
+        });
+
+        DifficultyBatchResult {
+            index,
+            source,
+            map_md5,
+            version: DifficultyProcessor::VERSION.to_string(),
+            overall_difficulty,
+            elapsed: start.elapsed(),
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|m| m.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_default();
+
+    format!("Difficulty calculation panicked: {}", message)
+}
@@ -1,9 +1,12 @@
-use crate::enums::quaver::{GameMode, ModIdentifier, QssPatternFlags};
+use crate::enums::quaver::{ModIdentifier, QssPatternFlags};
 use crate::maps::QuaverMap;
+use anyhow::{anyhow, Result};
 use std::collections::HashMap;
 
+use super::pattern_detection::{detect_note_patterns, pattern_coverage};
 use super::structures::{
-    FingerAction, FingerState, Hand, LnLayerType, StrainSolverData, StrainSolverHitObject,
+    DifficultyGraph, DifficultyTable, FingerAction, FingerState, Hand, HandLayout, LnLayerType,
+    RateDifficulty, Skillset, SkillsetRatings, StrainBin, StrainSolverData, StrainSolverHitObject,
 };
 use super::StrainConstants;
 
@@ -12,19 +15,24 @@ pub struct DifficultyProcessor {
     pub map: QuaverMap,
     pub overall_difficulty: f32,
     pub qss_pattern_flags: QssPatternFlags,
+    pub qss_pattern_coverage: HashMap<QssPatternFlags, f32>,
+    pub skillset_ratings: SkillsetRatings,
+    pub scratch_strain: f32,
     pub strain_constants: StrainConstants,
     pub average_note_density: f32,
     pub strain_solver_data: Vec<StrainSolverData>,
-    lane_to_hand_4k: HashMap<i32, Hand>,
-    lane_to_hand_7k: HashMap<i32, Hand>,
-    lane_to_finger_4k: HashMap<i32, FingerState>,
-    lane_to_finger_7k: HashMap<i32, FingerState>,
+    pub difficulty_graphs: Vec<DifficultyGraph>,
+    pub hand_layout: HandLayout,
     pub vibro_inaccuracy_confidence: f32,
     roll_inaccuracy_confidence: f32,
+    rate: f32,
+    note_patterns: HashMap<(i32, i32), QssPatternFlags>,
+    // Strain states at 1.0x with ambiguous lanes left unassigned, shared between rates.
+    base_strain_solver_data: Vec<StrainSolverData>,
 }
 
 impl DifficultyProcessor {
-    pub const VERSION: &'static str = "0.0.5";
+    pub const VERSION: &'static str = "0.0.6";
 
     pub fn new(
         map: &QuaverMap,
@@ -32,70 +40,130 @@ impl DifficultyProcessor {
         mods: Option<ModIdentifier>,
         detailed_solve: Option<bool>,
     ) -> Self {
-        let mut self_ = Self {
-            lane_to_hand_4k: HashMap::from([
-                (1, Hand::Left),
-                (2, Hand::Left),
-                (3, Hand::Right),
-                (4, Hand::Right),
-            ]),
-            lane_to_hand_7k: HashMap::from([
-                (1, Hand::Left),
-                (2, Hand::Left),
-                (3, Hand::Left),
-                (4, Hand::Ambiguous),
-                (5, Hand::Right),
-                (6, Hand::Right),
-                (7, Hand::Right),
-            ]),
-            lane_to_finger_4k: HashMap::from([
-                (1, FingerState::Middle),
-                (2, FingerState::Index),
-                (3, FingerState::Index),
-                (4, FingerState::Middle),
-            ]),
-            lane_to_finger_7k: HashMap::from([
-                (1, FingerState::Ring),
-                (2, FingerState::Middle),
-                (3, FingerState::Index),
-                (4, FingerState::Thumb),
-                (5, FingerState::Index),
-                (6, FingerState::Middle),
-                (7, FingerState::Ring),
-            ]),
-            ..Default::default()
-        };
+        let hand_layout = HandLayout::default_for(map.lane_layout());
+        Self::solve(map, constants, hand_layout, mods, detailed_solve)
+    }
 
-        self_.strain_constants = constants;
-        self_.map = map.clone();
+    // Like `new`, but with the given lane fingering instead of the default one for the map's layout.
+    pub fn with_hand_layout(
+        map: &QuaverMap,
+        constants: StrainConstants,
+        hand_layout: HandLayout,
+        mods: Option<ModIdentifier>,
+        detailed_solve: Option<bool>,
+    ) -> Result<Self> {
+        hand_layout.validate(map.lane_layout())?;
+
+        if let Some(hit_object) = map
+            .hit_objects
+            .iter()
+            .find(|h| !map.lane_layout().contains_lane(h.lane))
+        {
+            return Err(anyhow!(
+                "Hit object at {} ms is on lane {}, outside of the map's layout",
+                hit_object.start_time,
+                hit_object.lane
+            ));
+        }
+
+        Ok(Self::solve(
+            map,
+            constants,
+            hand_layout,
+            mods,
+            detailed_solve,
+        ))
+    }
+
+    fn solve(
+        map: &QuaverMap,
+        constants: StrainConstants,
+        hand_layout: HandLayout,
+        mods: Option<ModIdentifier>,
+        detailed_solve: Option<bool>,
+    ) -> Self {
+        let mut self_ = Self::unsolved(map, constants, hand_layout);
 
         if self_.map.hit_objects.len() < 2 {
             return self_;
         }
 
-        self_.calculate_difficulty(mods.unwrap_or(ModIdentifier::None));
+        self_.calculate_difficulty(mods.unwrap_or(ModIdentifier::None).rate());
 
         if detailed_solve.unwrap_or_default() {
             self_.compute_for_pattern_flags();
+            self_.compute_for_skillsets();
         }
 
         self_
     }
 
-    fn calculate_difficulty(&mut self, mods: ModIdentifier) {
-        let rate = mods.rate();
+    // Solves the map at each rate, or at every rate Quaver supports when none are given.
+    pub fn difficulty_table(
+        map: &QuaverMap,
+        constants: StrainConstants,
+        rates: Option<&[f32]>,
+        detailed_solve: Option<bool>,
+    ) -> DifficultyTable {
+        let rates = rates
+            .map(|r| r.to_vec())
+            .unwrap_or_else(ModIdentifier::rates);
+        let detailed_solve = detailed_solve.unwrap_or_default();
+        let unsolved = Self::unsolved(map, constants, HandLayout::default_for(map.lane_layout()));
+
+        let rates = rates
+            .into_iter()
+            .map(|rate| {
+                let mut processor = unsolved.clone();
+
+                if processor.map.hit_objects.len() >= 2 {
+                    processor.calculate_difficulty(rate);
+
+                    if detailed_solve {
+                        processor.compute_for_pattern_flags();
+                        processor.compute_for_skillsets();
+                    }
+                }
 
-        match self.map.mode {
-            GameMode::Keys4 => {
-                self.overall_difficulty = self.compute_for_overall_difficulty(rate, None)
-            }
-            GameMode::Keys7 => {
-                self.overall_difficulty = (self
-                    .compute_for_overall_difficulty(rate, Some(Hand::Left))
-                    + self.compute_for_overall_difficulty(rate, Some(Hand::Right)))
-                    / 2.
-            }
+                RateDifficulty {
+                    rate,
+                    overall_difficulty: processor.overall_difficulty,
+                    skillset_ratings: detailed_solve.then_some(processor.skillset_ratings),
+                }
+            })
+            .collect();
+
+        DifficultyTable {
+            version: Self::VERSION.to_string(),
+            rates,
+        }
+    }
+
+    fn unsolved(map: &QuaverMap, constants: StrainConstants, hand_layout: HandLayout) -> Self {
+        let mut self_ = Self {
+            hand_layout,
+            ..Default::default()
+        };
+
+        self_.strain_constants = constants;
+        self_.map = map.clone();
+        self_.compute_rate_independent_strain_states();
+
+        self_
+    }
+
+    fn calculate_difficulty(&mut self, rate: f32) {
+        self.rate = rate;
+
+        if self.hand_layout.has_ambiguous_hand() {
+            self.overall_difficulty = (self.compute_for_overall_difficulty(rate, Some(Hand::Left))
+                + self.compute_for_overall_difficulty(rate, Some(Hand::Right)))
+                / 2.
+        } else {
+            self.overall_difficulty = self.compute_for_overall_difficulty(rate, None)
         }
+
+        self.scratch_strain = self.difficulty_graph().scratch_strain;
     }
 
     fn compute_for_overall_difficulty(&mut self, rate: f32, assume_hand: Option<Hand>) -> f32 {
@@ -103,37 +171,44 @@ impl DifficultyProcessor {
         self.compute_base_strain_states(rate, assume_hand.unwrap_or_default());
         self.compute_for_chords();
         self.compute_for_finger_actions();
+        self.compute_for_scratch_chords();
         self.compute_for_roll_manipulation();
         self.compute_for_jack_manipulation();
         self.compute_for_ln_multiplier();
-        self.calculate_overall_difficulty()
+        self.calculate_overall_difficulty(assume_hand)
     }
 
-    fn compute_base_strain_states(&mut self, rate: f32, assume_hand: Hand) {
+    // One graph, or the average of both hand assignments when the layout has ambiguous lanes.
+    pub fn difficulty_graph(&self) -> DifficultyGraph {
+        DifficultyGraph::average(&self.difficulty_graphs)
+    }
+
+    fn compute_rate_independent_strain_states(&mut self) {
         for &hit_object in self.map.hit_objects.iter() {
-            if self.map.has_scratch_key && hit_object.lane == self.map.get_key_count(None) {
+            let Some(fingering) = self.hand_layout.lanes[&hit_object.lane] else {
                 continue;
-            }
+            };
 
             let mut cur_hit_ob = StrainSolverHitObject::new(hit_object);
-            let mut cur_strain_data: StrainSolverData;
+            cur_hit_ob.finger_state = fingering.finger;
 
-            match self.map.mode {
-                GameMode::Keys4 => {
-                    cur_hit_ob.finger_state = self.lane_to_finger_4k[&hit_object.lane];
-                    cur_strain_data = StrainSolverData::new(cur_hit_ob, Some(rate));
-                    cur_strain_data.hand = self.lane_to_hand_4k[&hit_object.lane];
-                }
-                GameMode::Keys7 => {
-                    cur_hit_ob.finger_state = self.lane_to_finger_7k[&hit_object.lane];
-                    cur_strain_data = StrainSolverData::new(cur_hit_ob, Some(rate));
-                    cur_strain_data.hand =
-                        if self.lane_to_hand_7k[&hit_object.lane] == Hand::Ambiguous {
-                            assume_hand
-                        } else {
-                            self.lane_to_hand_7k[&hit_object.lane]
-                        }
-                }
+            let mut cur_strain_data = StrainSolverData::new(cur_hit_ob, None);
+            cur_strain_data.hand = fingering.hand;
+
+            self.base_strain_solver_data.push(cur_strain_data);
+        }
+    }
+
+    fn compute_base_strain_states(&mut self, rate: f32, assume_hand: Hand) {
+        for base in self.base_strain_solver_data.iter() {
+            let hit_object = base.hit_objects[0].hit_object;
+            let mut cur_strain_data = base.clone();
+
+            cur_strain_data.start_time = hit_object.start_time as f32 / rate;
+            cur_strain_data.end_time = hit_object.end_time as f32 / rate;
+
+            if cur_strain_data.hand == Hand::Ambiguous {
+                cur_strain_data.hand = assume_hand;
             }
 
             self.strain_solver_data.push(cur_strain_data);
@@ -141,42 +216,67 @@ impl DifficultyProcessor {
     }
 
     fn compute_for_chords(&mut self) {
-        // had to do this because the size changes
-        for i in 0.. {
-            if i >= self.strain_solver_data.len() - 1 {
-                break;
-            }
-            for j in i + 1.. {
-                if j >= self.strain_solver_data.len() {
-                    break;
-                }
+        const END: usize = usize::MAX;
+
+        let len = self.strain_solver_data.len();
+        let tolerance = self.strain_constants.chord_clump_tolerance_ms;
+
+        // Merged entries are unlinked instead of removed, so merging stays linear. The list isn't
+        // always sorted (7K appends a second pass), so this walks it exactly like the original
+        // in-place version did, including skipping the entry after each merged one.
+        let mut next: Vec<usize> = (1..=len).map(|i| if i < len { i } else { END }).collect();
+        let mut merged = vec![false; len];
+
+        let mut i = if len > 1 { 0 } else { END };
+        while i != END && next[i] != END {
+            let mut previous = i;
+            let mut j = next[i];
 
+            while j != END {
                 let ms_diff =
                     self.strain_solver_data[j].start_time - self.strain_solver_data[i].start_time;
 
-                if ms_diff > self.strain_constants.chord_clump_tolerance_ms {
+                if ms_diff > tolerance {
                     break;
                 }
 
-                if ms_diff.abs() <= self.strain_constants.chord_clump_tolerance_ms
+                if ms_diff.abs() <= tolerance
                     && self.strain_solver_data[i].hand == self.strain_solver_data[j].hand
                 {
-                    for k in self.strain_solver_data[j].hit_objects.clone() {
-                        let mut same_state_found = false;
-                        for l in self.strain_solver_data[i].hit_objects.iter() {
-                            if l.finger_state == k.finger_state {
-                                same_state_found = true;
-                            }
-                        }
+                    let hit_objects = std::mem::take(&mut self.strain_solver_data[j].hit_objects);
+                    for k in hit_objects {
+                        let same_state_found = self.strain_solver_data[i]
+                            .hit_objects
+                            .iter()
+                            .any(|l| l.finger_state == k.finger_state);
 
                         if !same_state_found {
                             self.strain_solver_data[i].hit_objects.push(k);
                         }
                     }
 
-                    self.strain_solver_data.remove(j);
+                    merged[j] = true;
+                    next[previous] = next[j];
+
+                    // The entry that took j's place is never compared against i.
+                    previous = next[j];
+                    j = match previous {
+                        END => END,
+                        skipped => next[skipped],
+                    };
+                } else {
+                    previous = j;
+                    j = next[j];
                 }
             }
+
+            i = next[i];
+        }
+
+        if merged.contains(&true) {
+            let mut merged = merged.into_iter();
+            self.strain_solver_data
+                .retain(|_| !merged.next().unwrap_or_default());
         }
 
         for qss_data in self.strain_solver_data.iter_mut() {
@@ -216,6 +316,17 @@ impl DifficultyProcessor {
                                 self.strain_constants.roll_max_strain_value,
                                 self.strain_constants.roll_curve_exponential,
                             );
+                    } else if action_same_state && self.strain_solver_data[i].hand == Hand::Scratch
+                    {
+                        self.strain_solver_data[i].finger_action = FingerAction::SimpleJack;
+                        self.strain_solver_data[i].action_strain_coefficient = self
+                            .get_coefficient_value(
+                                action_duration,
+                                self.strain_constants.scratch_jack_lower_boundary_ms,
+                                self.strain_constants.scratch_jack_upper_boundary_ms,
+                                self.strain_constants.scratch_jack_max_strain_value,
+                                self.strain_constants.scratch_jack_curve_exponential,
+                            );
                     } else if action_same_state {
                         self.strain_solver_data[i].finger_action = FingerAction::SimpleJack;
                         self.strain_solver_data[i].action_strain_coefficient = self
@@ -253,6 +364,45 @@ impl DifficultyProcessor {
         }
     }
 
+    // Scratch notes are on their own hand, so a scratch hit together with keys never counts as a
+    // chord in the finger action pass. Both sides of such a chord get the scratch multiplier.
+    fn compute_for_scratch_chords(&mut self) {
+        let sorted_times = |scratch: bool| -> Vec<f32> {
+            let mut times: Vec<f32> = self
+                .strain_solver_data
+                .iter()
+                .filter(|s| (s.hand == Hand::Scratch) == scratch)
+                .map(|s| s.start_time)
+                .collect();
+            times.sort_by(f32::total_cmp);
+            times
+        };
+
+        let scratch_times = sorted_times(true);
+        if scratch_times.is_empty() {
+            return;
+        }
+
+        let key_times = sorted_times(false);
+        let tolerance = self.strain_constants.chord_clump_tolerance_ms;
+        let has_time_near = |times: &[f32], time: f32| {
+            let next = times.partition_point(|&t| t < time - tolerance);
+            times.get(next).is_some_and(|&t| t <= time + tolerance)
+        };
+
+        for data in self.strain_solver_data.iter_mut() {
+            let other_times = if data.hand == Hand::Scratch {
+                &key_times
+            } else {
+                &scratch_times
+            };
+
+            if has_time_near(other_times, data.start_time) {
+                data.pattern_strain_multiplier = self.strain_constants.scratch_chord_multiplier;
+            }
+        }
+    }
+
     fn compute_for_roll_manipulation(&mut self) {
         let mut manipulation_index = 0.;
 
@@ -413,16 +563,156 @@ impl DifficultyProcessor {
     }
 
     fn compute_for_pattern_flags(&mut self) {
-        if self.vibro_inaccuracy_confidence / self.strain_solver_data.len() as f32 > 0.10 {
+        const PATTERN_COVERAGE_THRESHOLD: f32 = 0.10;
+
+        let vibro_coverage =
+            self.vibro_inaccuracy_confidence / self.strain_solver_data.len() as f32;
+        let roll_coverage = self.roll_inaccuracy_confidence / self.strain_solver_data.len() as f32;
+
+        if vibro_coverage > 0.10 {
             self.qss_pattern_flags |= QssPatternFlags::SimpleVibro;
         }
 
-        if self.roll_inaccuracy_confidence / self.strain_solver_data.len() as f32 > 0.15 {
+        if roll_coverage > 0.15 {
             self.qss_pattern_flags |= QssPatternFlags::Rolls;
         }
+
+        self.note_patterns =
+            detect_note_patterns(&self.strain_solver_data, &self.strain_constants, self.rate);
+        self.qss_pattern_coverage = pattern_coverage(&self.note_patterns);
+        self.qss_pattern_coverage
+            .insert(QssPatternFlags::SimpleVibro, vibro_coverage.min(1.));
+        self.qss_pattern_coverage
+            .insert(QssPatternFlags::Rolls, roll_coverage.min(1.));
+
+        for (&pattern, &coverage) in self.qss_pattern_coverage.iter() {
+            if pattern != QssPatternFlags::SimpleVibro
+                && pattern != QssPatternFlags::Rolls
+                && coverage >= PATTERN_COVERAGE_THRESHOLD
+            {
+                self.qss_pattern_flags |= pattern;
+            }
+        }
+    }
+
+    // Each skillset is rated from the hardest 40% of bins, counting only the strain of objects
+    // that are part of its patterns or finger actions.
+    fn compute_for_skillsets(&mut self) {
+        let graph = self.difficulty_graph();
+        let map_start = match graph.bins.first() {
+            Some(bin) => bin.start_time,
+            None => return,
+        };
+
+        self.skillset_ratings.overall = self.overall_difficulty;
+
+        for skillset in Skillset::ALL {
+            let mut relevant_strain = vec![0f32; graph.bins.len()];
+            let mut counts = vec![0usize; graph.bins.len()];
+
+            for data in self.strain_solver_data.iter() {
+                let index = ((data.start_time - map_start) / graph.bin_size as f32).floor();
+                if index < 0. || index as usize >= graph.bins.len() {
+                    continue;
+                }
+
+                let index = index as usize;
+                counts[index] += 1;
+
+                let patterns = data
+                    .hit_objects
+                    .iter()
+                    .filter_map(|h| {
+                        self.note_patterns
+                            .get(&(h.hit_object.start_time, h.hit_object.lane))
+                    })
+                    .fold(QssPatternFlags::empty(), |a, &b| a | b);
+
+                let relevant = patterns.intersects(skillset.patterns())
+                    || skillset.finger_actions().contains(&data.finger_action)
+                    || (skillset == Skillset::LongNote && data.end_time > data.start_time);
+
+                if relevant {
+                    relevant_strain[index] += data.total_strain_value;
+                }
+            }
+
+            let mut bins: Vec<f32> = relevant_strain
+                .iter()
+                .zip(counts.iter())
+                .map(|(&strain, &count)| if count > 0 { strain / count as f32 } else { 0. })
+                .collect();
+            bins.sort_by(|a, b| b.total_cmp(a));
+
+            let cutoff_pos = ((bins.len() as f32 * 0.4).floor() as usize).max(1);
+            let top_40 = &bins[..cutoff_pos.min(bins.len())];
+            let rating = top_40.iter().sum::<f32>() / top_40.len() as f32;
+
+            self.skillset_ratings
+                .set(skillset, rating * graph.short_map_adjustment);
+        }
     }
 
-    fn calculate_overall_difficulty(&mut self) -> f32 {
+    pub fn pattern_coverage(&self, pattern: QssPatternFlags) -> f32 {
+        self.qss_pattern_coverage
+            .get(&pattern)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn strain_bins(&self, map_start: f32, map_end: f32, hand: Option<Hand>) -> Vec<StrainBin> {
+        const BIN_SIZE: i32 = 1000;
+
+        let bin_starts: Vec<f32> = ((map_start * 100.) as i32..(map_end * 100.) as i32)
+            .step_by((BIN_SIZE * 100) as usize)
+            .map(|x| x as f32 * 0.01)
+            .collect();
+
+        // Same starting value as `Iterator::sum`.
+        let mut sums: Vec<f32> = vec![-0.; bin_starts.len()];
+        let mut counts: Vec<usize> = vec![0; bin_starts.len()];
+
+        if let Some(&first) = bin_starts.first() {
+            for data in self
+                .strain_solver_data
+                .iter()
+                .filter(|s| hand.is_none_or(|h| s.hand == h))
+            {
+                // Bin edges are rounded f32s, so check the neighbouring bins with the exact same
+                // comparison instead of trusting the division. Entries are added in list order to
+                // keep the sums identical.
+                let estimate = ((data.start_time - first) / BIN_SIZE as f32).floor();
+                if !estimate.is_finite() || estimate < -1. || estimate > bin_starts.len() as f32 {
+                    continue;
+                }
+
+                let estimate = estimate as i64;
+                for bin in (estimate - 1).max(0)..=(estimate + 1) {
+                    let bin = bin as usize;
+                    let Some(&bin_start) = bin_starts.get(bin) else {
+                        break;
+                    };
+
+                    if data.start_time >= bin_start && data.start_time < bin_start + BIN_SIZE as f32
+                    {
+                        sums[bin] += data.total_strain_value;
+                        counts[bin] += 1;
+                    }
+                }
+            }
+        }
+
+        bin_starts
+            .into_iter()
+            .zip(sums.into_iter().zip(counts))
+            .map(|(start_time, (sum, count))| StrainBin {
+                start_time,
+                strain: if count > 0 { sum / count as f32 } else { 0. },
+            })
+            .collect()
+    }
+
+    fn calculate_overall_difficulty(&mut self, assume_hand: Option<Hand>) -> f32 {
         let mut calculated_diff: f32;
 
         for data in self.strain_solver_data.iter_mut() {
@@ -441,7 +731,31 @@ impl DifficultyProcessor {
                 .filter(|s| s.hand == Hand::Left || s.hand == Hand::Right)
                 .count() as f32;
 
-        let mut bins: Vec<f32> = Vec::new();
+        let scratch_strain: Vec<f32> = self
+            .strain_solver_data
+            .iter()
+            .filter(|s| s.hand == Hand::Scratch)
+            .map(|s| s.total_strain_value)
+            .collect();
+
+        // Scratch notes only ever add to the rating, a few easy ones don't make the keys easier.
+        if !scratch_strain.is_empty() {
+            let strain_count = self
+                .strain_solver_data
+                .iter()
+                .filter(|s| s.hand != Hand::Ambiguous)
+                .count();
+            let with_scratch = self
+                .strain_solver_data
+                .iter()
+                .filter(|s| s.hand != Hand::Ambiguous)
+                .map(|s| s.total_strain_value)
+                .sum::<f32>()
+                / strain_count as f32;
+
+            calculated_diff = calculated_diff.max(with_scratch);
+        }
+
         const BIN_SIZE: i32 = 1000;
 
         let map_start = self
@@ -457,30 +771,26 @@ impl DifficultyProcessor {
             .reduce(f32::max)
             .unwrap_or_default();
 
-        for i in ((map_start * 100.) as i32..(map_end * 100.) as i32)
-            .step_by((BIN_SIZE * 100) as usize)
-            .map(|x| x as f32 * 0.01)
-        {
-            let values_in_bin: Vec<_> = self
-                .strain_solver_data
-                .iter()
-                .filter(|s| s.start_time >= i && s.start_time < i + BIN_SIZE as f32)
-                .collect();
-
-            let average_rating = if !values_in_bin.is_empty() {
-                values_in_bin
-                    .iter()
-                    .map(|s| s.total_strain_value)
-                    .sum::<f32>()
-                    / values_in_bin.len() as f32
+        let mut graph = DifficultyGraph {
+            assumed_hand: assume_hand,
+            bin_size: BIN_SIZE,
+            bins: self.strain_bins(map_start, map_end, None),
+            left_hand_bins: self.strain_bins(map_start, map_end, Some(Hand::Left)),
+            right_hand_bins: self.strain_bins(map_start, map_end, Some(Hand::Right)),
+            scratch_bins: self.strain_bins(map_start, map_end, Some(Hand::Scratch)),
+            average_strain: calculated_diff,
+            scratch_strain: if !scratch_strain.is_empty() {
+                scratch_strain.iter().sum::<f32>() / scratch_strain.len() as f32
             } else {
                 0.
-            };
+            },
+            ..Default::default()
+        };
 
-            bins.push(average_rating);
-        }
+        let bins: Vec<f32> = graph.bins.iter().map(|b| b.strain).collect();
 
         if !bins.iter().any(|&strain| strain > 0.) {
+            self.difficulty_graphs.push(graph);
             return 0.;
         };
 
@@ -536,6 +846,14 @@ impl DifficultyProcessor {
         let short_map_adjustment = (0.25 * f32::sqrt(true_drain_time / SHORT_MAP_THRESHOLD) + 0.75)
             .clamp(MAX_SHORT_MAP_ADJUSTMENT, 1.);
 
+        graph.easy_rating_cutoff = easy_rating_cutoff;
+        graph.cutoff_bin_count = cutoff_pos;
+        graph.continuity = continuity;
+        graph.continuity_adjustment = continuity_adjustment;
+        graph.short_map_adjustment = short_map_adjustment;
+        graph.difficulty = calculated_diff * short_map_adjustment;
+        self.difficulty_graphs.push(graph);
+
         calculated_diff * short_map_adjustment
     }
 
@@ -1,6 +1,11 @@
+mod constants_fitting;
+mod difficulty_batch;
 mod difficulty_processor;
+mod pattern_detection;
 mod strain_constants;
 pub mod structures;
 
+pub use constants_fitting::*;
+pub use difficulty_batch::*;
 pub use difficulty_processor::*;
 pub use strain_constants::*;
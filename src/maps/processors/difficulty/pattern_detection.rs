@@ -0,0 +1,427 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use super::structures::{FingerAction, FingerState, Hand, LnLayerType, StrainSolverData};
+use super::StrainConstants;
+use crate::enums::quaver::QssPatternFlags;
+
+const INVERSE_LN_GAP_MS: f32 = 150.;
+const POLYRHYTHM_MAX_INTERVAL_MS: f32 = 500.;
+const POLYRHYTHM_RATIO_TOLERANCE: f32 = 0.2;
+
+#[derive(Clone, Copy, Debug)]
+struct PatternNote {
+    start_time: i32,
+    time: f32,
+    end_time: f32,
+    lane: i32,
+    hand: Hand,
+    finger: FingerState,
+    data: usize,
+    ln_layer_type: LnLayerType,
+}
+
+// A hand moving from one row to the next row it plays, as solved by the finger action pass.
+#[derive(Clone, Copy, Debug)]
+struct HandLink {
+    to: usize,
+    hand: Hand,
+    action: FingerAction,
+    duration: f32,
+    fingers: FingerState,
+}
+
+impl HandLink {
+    fn is_jack(&self) -> bool {
+        matches!(
+            self.action,
+            FingerAction::SimpleJack | FingerAction::TechnicalJack
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PatternRow {
+    time: f32,
+    lanes: BTreeSet<i32>,
+    notes: Vec<usize>,
+}
+
+impl PatternRow {
+    fn size(&self) -> usize {
+        self.lanes.len()
+    }
+}
+
+// Share of the map's notes that are part of each pattern. `SimpleVibro` and `Rolls` come from the
+// manipulation passes and are not covered here.
+pub(super) fn pattern_coverage(
+    patterns: &HashMap<(i32, i32), QssPatternFlags>,
+) -> HashMap<QssPatternFlags, f32> {
+    let mut counts: HashMap<QssPatternFlags, usize> = HashMap::new();
+
+    for flags in patterns.values() {
+        for pattern in PATTERNS {
+            if flags.contains(pattern) {
+                *counts.entry(pattern).or_default() += 1;
+            }
+        }
+    }
+
+    counts
+        .into_iter()
+        .map(|(pattern, count)| (pattern, count as f32 / patterns.len() as f32))
+        .collect()
+}
+
+const PATTERNS: [QssPatternFlags; 15] = [
+    QssPatternFlags::MiniJack,
+    QssPatternFlags::ChordJack,
+    QssPatternFlags::KoreaJack,
+    QssPatternFlags::LongJack,
+    QssPatternFlags::QuadJack,
+    QssPatternFlags::LightStream,
+    QssPatternFlags::JumpStream,
+    QssPatternFlags::HandStream,
+    QssPatternFlags::QuadStream,
+    QssPatternFlags::InverseLN,
+    QssPatternFlags::ReleaseLN,
+    QssPatternFlags::Polyrhythm,
+    QssPatternFlags::JumpTrill,
+    QssPatternFlags::SplitTrill,
+    QssPatternFlags::ControlVibro,
+];
+
+// Patterns each note is part of, keyed by its unscaled start time and lane.
+pub(super) fn detect_note_patterns(
+    strain_solver_data: &[StrainSolverData],
+    constants: &StrainConstants,
+    rate: f32,
+) -> HashMap<(i32, i32), QssPatternFlags> {
+    let notes = pattern_notes(strain_solver_data, rate);
+    let rows = pattern_rows(&notes, constants.chord_clump_tolerance_ms);
+
+    let mut marked: HashMap<QssPatternFlags, HashSet<usize>> = HashMap::new();
+    let mut mark = |pattern: QssPatternFlags, indices: &[usize]| {
+        marked
+            .entry(pattern)
+            .or_default()
+            .extend(indices.iter().copied());
+    };
+
+    let jack_threshold = constants.s_jack_upper_boundary_ms;
+    let stream_threshold = constants.roll_upper_boundary_ms;
+    let vibro_threshold = constants.vibro_action_duration_ms + constants.vibro_action_tolerance_ms;
+
+    // `links[i]` holds every hand that moves on from row i.
+    let links = hand_links(strain_solver_data, &notes, &rows);
+    let links_between = |from: usize, to: usize| links[from].iter().filter(move |l| l.to == to);
+    let jacks_between = |from: usize, to: usize| {
+        links_between(from, to).filter(|l| l.is_jack() && l.duration <= jack_threshold)
+    };
+
+    // Fingers that continue a single note jack from the previous row, used to measure jack length.
+    let mut jack_links: Vec<HashSet<(Hand, FingerState)>> = vec![HashSet::new(); rows.len()];
+
+    for i in 1..rows.len() {
+        let (previous, current) = (&rows[i - 1], &rows[i]);
+        let gap = current.time - previous.time;
+        let fingers: Vec<(Hand, FingerState)> = jacks_between(i - 1, i)
+            .flat_map(|l| fingers(l.fingers).map(move |f| (l.hand, f)))
+            .collect();
+
+        if fingers.is_empty() {
+            continue;
+        }
+
+        if current.size() >= 2 && previous.size() >= 2 {
+            let pattern = if fingers.len() >= 4 {
+                QssPatternFlags::QuadJack
+            } else if current.lanes == previous.lanes {
+                QssPatternFlags::KoreaJack
+            } else {
+                QssPatternFlags::ChordJack
+            };
+
+            mark(pattern, &previous.notes);
+            mark(pattern, &current.notes);
+
+            if gap <= vibro_threshold {
+                mark(QssPatternFlags::ControlVibro, &previous.notes);
+                mark(QssPatternFlags::ControlVibro, &current.notes);
+            }
+        } else {
+            jack_links[i] = fingers.into_iter().collect();
+        }
+    }
+
+    // Two or three hits in a row are a minijack, anything longer is a longjack.
+    let jack_fingers: HashSet<(Hand, FingerState)> = jack_links.iter().flatten().copied().collect();
+    for (hand, finger) in jack_fingers {
+        let mut start = 0;
+        for i in 1..=rows.len() {
+            if i < rows.len() && jack_links[i].contains(&(hand, finger)) {
+                continue;
+            }
+
+            let length = i - start;
+            let pattern = match length {
+                2..=3 => Some(QssPatternFlags::MiniJack),
+                l if l >= 4 => Some(QssPatternFlags::LongJack),
+                _ => None,
+            };
+
+            if let Some(pattern) = pattern {
+                let chain: Vec<usize> = rows[start..i]
+                    .iter()
+                    .flat_map(|row| row.notes.iter().copied())
+                    .filter(|&n| notes[n].hand == hand && notes[n].finger.contains(finger))
+                    .collect();
+                mark(pattern, &chain);
+            }
+
+            start = i;
+        }
+    }
+
+    // Rows stream into their neighbours when every hand rolls or brackets between them.
+    for (i, row) in rows.iter().enumerate() {
+        let streams_with = |other: usize| {
+            let (from, to) = (i.min(other), i.max(other));
+            to < rows.len()
+                && (rows[to].time - rows[from].time) <= stream_threshold
+                && links_between(from, to).all(|l| !l.is_jack())
+        };
+
+        if (i > 0 && streams_with(i - 1)) || streams_with(i + 1) {
+            let pattern = match row.size() {
+                1 => QssPatternFlags::LightStream,
+                2 => QssPatternFlags::JumpStream,
+                3 => QssPatternFlags::HandStream,
+                _ => QssPatternFlags::QuadStream,
+            };
+
+            mark(pattern, &row.notes);
+        }
+    }
+
+    // Two note rows alternating with no jack in between. Hands that come back to the same fingers a
+    // row later make a jumptrill, hands that roll into the middle row make a split trill.
+    for i in 1..rows.len().saturating_sub(1) {
+        let (previous, current, next) = (&rows[i - 1], &rows[i], &rows[i + 1]);
+
+        if previous.size() != 2 || current.size() != 2 || next.size() != 2 {
+            continue;
+        }
+
+        if previous.lanes != next.lanes
+            || jacks_between(i - 1, i).next().is_some()
+            || jacks_between(i, i + 1).next().is_some()
+            || current.time - previous.time > stream_threshold
+            || next.time - current.time > stream_threshold
+        {
+            continue;
+        }
+
+        let returns = links_between(i - 1, i + 1).collect::<Vec<_>>();
+        let rolls = links_between(i - 1, i).collect::<Vec<_>>();
+
+        let pattern = if !returns.is_empty()
+            && rolls.is_empty()
+            && returns.iter().all(|l| l.action == FingerAction::SimpleJack)
+        {
+            QssPatternFlags::JumpTrill
+        } else if !rolls.is_empty()
+            && rolls
+                .iter()
+                .all(|l| matches!(l.action, FingerAction::Roll | FingerAction::Bracket))
+        {
+            QssPatternFlags::SplitTrill
+        } else {
+            continue;
+        };
+
+        mark(pattern, &previous.notes);
+        mark(pattern, &current.notes);
+        mark(pattern, &next.notes);
+    }
+
+    for (i, note) in notes.iter().enumerate() {
+        if matches!(
+            note.ln_layer_type,
+            LnLayerType::InsideRelease | LnLayerType::OutsideRelease
+        ) {
+            marked
+                .entry(QssPatternFlags::ReleaseLN)
+                .or_default()
+                .insert(i);
+        }
+
+        if note.end_time <= note.time {
+            continue;
+        }
+
+        let next_in_lane = notes[i + 1..].iter().position(|n| n.lane == note.lane);
+        if let Some(j) = next_in_lane.map(|j| j + i + 1) {
+            let gap = notes[j].time - note.end_time;
+            if notes[j].end_time > notes[j].time && (0. ..=INVERSE_LN_GAP_MS).contains(&gap) {
+                let inverse = marked.entry(QssPatternFlags::InverseLN).or_default();
+                inverse.insert(i);
+                inverse.insert(j);
+            }
+        }
+    }
+
+    marked
+        .entry(QssPatternFlags::Polyrhythm)
+        .or_default()
+        .extend(polyrhythm_notes(&notes, &rows));
+
+    let mut patterns: Vec<QssPatternFlags> = vec![QssPatternFlags::empty(); notes.len()];
+    for (pattern, indices) in marked {
+        for i in indices {
+            patterns[i] |= pattern;
+        }
+    }
+
+    notes
+        .iter()
+        .zip(patterns)
+        .map(|(note, pattern)| ((note.start_time, note.lane), pattern))
+        .collect()
+}
+
+// 7K is solved once per thumb assignment, so the same note can appear twice.
+fn pattern_notes(strain_solver_data: &[StrainSolverData], rate: f32) -> Vec<PatternNote> {
+    let mut seen: HashSet<(i32, i32)> = HashSet::new();
+    let mut notes: Vec<PatternNote> = Vec::new();
+
+    for (i, data) in strain_solver_data.iter().enumerate() {
+        for hit_ob in data.hit_objects.iter() {
+            let hit_object = hit_ob.hit_object;
+            if !seen.insert((hit_object.start_time, hit_object.lane)) {
+                continue;
+            }
+
+            notes.push(PatternNote {
+                start_time: hit_object.start_time,
+                time: hit_object.start_time as f32 / rate,
+                end_time: hit_object.end_time as f32 / rate,
+                lane: hit_object.lane,
+                hand: data.hand,
+                finger: hit_ob.finger_state,
+                data: i,
+                ln_layer_type: hit_ob.ln_layer_type,
+            });
+        }
+    }
+
+    notes.sort_by(|a, b| a.time.total_cmp(&b.time).then(a.lane.cmp(&b.lane)));
+    notes
+}
+
+fn pattern_rows(notes: &[PatternNote], chord_tolerance: f32) -> Vec<PatternRow> {
+    let mut rows: Vec<PatternRow> = Vec::new();
+
+    for (i, note) in notes.iter().enumerate() {
+        match rows.last_mut() {
+            Some(row) if note.time - row.time <= chord_tolerance => {
+                row.lanes.insert(note.lane);
+                row.notes.push(i);
+            }
+            _ => rows.push(PatternRow {
+                time: note.time,
+                lanes: BTreeSet::from([note.lane]),
+                notes: vec![i],
+            }),
+        }
+    }
+
+    rows
+}
+
+// Each solved hand data is placed in the row of its first note. Data from the second 7K pass only
+// holds notes already seen in the first one, so it is left out.
+fn hand_links(
+    strain_solver_data: &[StrainSolverData],
+    notes: &[PatternNote],
+    rows: &[PatternRow],
+) -> Vec<Vec<HandLink>> {
+    let mut data_rows: HashMap<usize, usize> = HashMap::new();
+    for (i, row) in rows.iter().enumerate() {
+        for &n in row.notes.iter() {
+            data_rows.entry(notes[n].data).or_insert(i);
+        }
+    }
+
+    let mut links: Vec<Vec<HandLink>> = vec![Vec::new(); rows.len()];
+    for (&data, &from) in data_rows.iter() {
+        let current = &strain_solver_data[data];
+        let next = match current.next_strain_solver_data_on_current_hand {
+            Some(next) => next,
+            None => continue,
+        };
+
+        if let Some(&to) = data_rows.get(&next) {
+            links[from].push(HandLink {
+                to,
+                hand: current.hand,
+                action: current.finger_action,
+                duration: current.finger_action_duration_ms,
+                fingers: current.finger_state & strain_solver_data[next].finger_state,
+            });
+        }
+    }
+
+    links
+}
+
+fn fingers(state: FingerState) -> impl Iterator<Item = FingerState> {
+    (0..8)
+        .map(|bit| FingerState::from_bits_truncate(1 << bit))
+        .filter(move |&finger| !finger.is_empty() && state.contains(finger))
+}
+
+// Notes where both hands keep their own pulse and the two pulses don't divide into each other.
+fn polyrhythm_notes(notes: &[PatternNote], rows: &[PatternRow]) -> Vec<usize> {
+    let hand_rows = |hand: Hand| -> Vec<&PatternRow> {
+        rows.iter()
+            .filter(|r| r.notes.iter().any(|&n| notes[n].hand == hand))
+            .collect()
+    };
+
+    let left = hand_rows(Hand::Left);
+    let right = hand_rows(Hand::Right);
+
+    let mut found: Vec<usize> = Vec::new();
+    let mut j = 0;
+
+    for l in left.windows(2) {
+        let left_interval = l[1].time - l[0].time;
+        if left_interval > POLYRHYTHM_MAX_INTERVAL_MS {
+            continue;
+        }
+
+        while j + 1 < right.len() && right[j + 1].time <= l[0].time {
+            j += 1;
+        }
+
+        let r = match right.get(j..j + 2) {
+            Some(r) => r,
+            None => break,
+        };
+
+        let right_interval = r[1].time - r[0].time;
+        if right_interval > POLYRHYTHM_MAX_INTERVAL_MS || r[1].time < l[0].time {
+            continue;
+        }
+
+        let ratio = left_interval.max(right_interval) / left_interval.min(right_interval);
+        if (ratio - ratio.round()).abs() > POLYRHYTHM_RATIO_TOLERANCE {
+            for row in l.iter().chain(r.iter()) {
+                found.extend(row.notes.iter().copied());
+            }
+        }
+    }
+
+    found
+}
@@ -1,5 +1,15 @@
-#[derive(Default, Clone)]
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+const BUNDLED_PROFILES: &str = include_str!("strain_profiles.yaml");
+
+// Missing fields in a profile file fall back to the default constants rather than zero.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+#[serde(default = "StrainConstants::new")]
 pub struct StrainConstants {
+    pub name: String,
+
     // Other
     pub chord_clump_tolerance_ms: f32,
 
@@ -48,11 +58,26 @@ pub struct StrainConstants {
     pub roll_ratio_multiplier: f32,
     pub roll_length_multiplier: f32,
     pub roll_max_length: f32,
+
+    // Scratch
+    pub scratch_jack_lower_boundary_ms: f32,
+    pub scratch_jack_upper_boundary_ms: f32,
+    pub scratch_jack_max_strain_value: f32,
+    pub scratch_jack_curve_exponential: f32,
+    pub scratch_chord_multiplier: f32,
+}
+
+impl Default for StrainConstants {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl StrainConstants {
     pub fn new() -> Self {
         Self {
+            name: "Default".to_string(),
+
             // Simple Jacks
             s_jack_lower_boundary_ms: 40.0,
             s_jack_upper_boundary_ms: 320.0,
@@ -99,7 +124,66 @@ impl StrainConstants {
             roll_length_multiplier: 0.6,
             roll_max_length: 14.,
 
+            // Scratch
+            scratch_jack_lower_boundary_ms: 40.0,
+            scratch_jack_upper_boundary_ms: 320.0,
+            scratch_jack_max_strain_value: 68.0,
+            scratch_jack_curve_exponential: 1.17,
+            scratch_chord_multiplier: 1.15,
+
             chord_clump_tolerance_ms: 8.,
         }
     }
+
+    pub fn profiles() -> Vec<Self> {
+        Self::from_string(BUNDLED_PROFILES).expect("Bundled strain profiles are invalid")
+    }
+
+    pub fn profile(name: &str) -> Option<Self> {
+        Self::profiles()
+            .into_iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    // Every tunable value, keyed by its name in profile files.
+    pub fn parameters(&self) -> Result<Vec<(String, f32)>> {
+        let value = serde_yaml::to_value(self)?;
+        let mapping = value
+            .as_mapping()
+            .ok_or_else(|| anyhow!("Strain constants did not serialize to a mapping"))?;
+
+        Ok(mapping
+            .iter()
+            .filter_map(|(key, value)| Some((key.as_str()?.to_string(), value.as_f64()? as f32)))
+            .collect())
+    }
+
+    pub fn with_parameter(&self, name: &str, value: f32) -> Result<Self> {
+        let mut constants = serde_yaml::to_value(self)?;
+        let parameter = constants
+            .get_mut(name)
+            .filter(|p| p.is_f64())
+            .ok_or_else(|| anyhow!("Unknown strain constant: {}", name))?;
+        *parameter = serde_yaml::Value::from(value as f64);
+
+        Ok(serde_yaml::from_value(constants)?)
+    }
+
+    pub fn from_path(path: &str) -> Result<Vec<Self>> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_yaml::from_reader(file)?)
+    }
+
+    pub fn from_string(input: &str) -> Result<Vec<Self>> {
+        Ok(serde_yaml::from_str(input)?)
+    }
+
+    pub fn to_string(profiles: &[Self]) -> Result<String> {
+        Ok(serde_yaml::to_string(profiles)?)
+    }
+
+    pub fn to_file(profiles: &[Self], path: &str) -> Result<()> {
+        let w = std::fs::File::create(path)?;
+        Ok(serde_yaml::to_writer(w, profiles)?)
+    }
 }
@@ -0,0 +1,85 @@
+use super::Hand;
+
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub struct StrainBin {
+    pub start_time: f32,
+    pub strain: f32,
+}
+
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct DifficultyGraph {
+    // 7K is solved once with the thumb lane on each hand.
+    pub assumed_hand: Option<Hand>,
+    pub bin_size: i32,
+    pub bins: Vec<StrainBin>,
+    pub left_hand_bins: Vec<StrainBin>,
+    pub right_hand_bins: Vec<StrainBin>,
+    pub scratch_bins: Vec<StrainBin>,
+    // Average of the hardest 40% of bins, which continuity is measured against.
+    pub easy_rating_cutoff: f32,
+    pub cutoff_bin_count: usize,
+    pub average_strain: f32,
+    // Average strain of the scratch notes alone, 0 without any.
+    pub scratch_strain: f32,
+    pub continuity: f32,
+    pub continuity_adjustment: f32,
+    pub short_map_adjustment: f32,
+    pub difficulty: f32,
+}
+
+impl DifficultyGraph {
+    pub fn hardest_section(&self) -> Option<StrainBin> {
+        self.hardest_sections(1).first().copied()
+    }
+
+    pub fn hardest_sections(&self, count: usize) -> Vec<StrainBin> {
+        let mut sorted = self.bins.clone();
+        sorted.sort_by(|a, b| b.strain.total_cmp(&a.strain));
+        sorted.truncate(count);
+        sorted
+    }
+
+    // Averages graphs bin by bin, as the 7K difficulty averages both thumb assignments.
+    pub fn average(graphs: &[DifficultyGraph]) -> Self {
+        let first = match graphs.first() {
+            Some(first) if graphs.len() > 1 => first,
+            _ => return graphs.first().cloned().unwrap_or_default(),
+        };
+
+        let count = graphs.len() as f32;
+        let average_bins = |select: fn(&DifficultyGraph) -> &Vec<StrainBin>| -> Vec<StrainBin> {
+            select(first)
+                .iter()
+                .enumerate()
+                .map(|(i, bin)| StrainBin {
+                    start_time: bin.start_time,
+                    strain: graphs
+                        .iter()
+                        .map(|g| select(g).get(i).map(|b| b.strain).unwrap_or_default())
+                        .sum::<f32>()
+                        / count,
+                })
+                .collect()
+        };
+        let average_value = |select: fn(&DifficultyGraph) -> f32| -> f32 {
+            graphs.iter().map(select).sum::<f32>() / count
+        };
+
+        Self {
+            assumed_hand: None,
+            bin_size: first.bin_size,
+            bins: average_bins(|g| &g.bins),
+            left_hand_bins: average_bins(|g| &g.left_hand_bins),
+            right_hand_bins: average_bins(|g| &g.right_hand_bins),
+            scratch_bins: average_bins(|g| &g.scratch_bins),
+            easy_rating_cutoff: average_value(|g| g.easy_rating_cutoff),
+            cutoff_bin_count: first.cutoff_bin_count,
+            average_strain: average_value(|g| g.average_strain),
+            scratch_strain: average_value(|g| g.scratch_strain),
+            continuity: average_value(|g| g.continuity),
+            continuity_adjustment: average_value(|g| g.continuity_adjustment),
+            short_map_adjustment: average_value(|g| g.short_map_adjustment),
+            difficulty: average_value(|g| g.difficulty),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::SkillsetRatings;
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+#[serde(default)]
+pub struct RateDifficulty {
+    pub rate: f32,
+    pub overall_difficulty: f32,
+    pub skillset_ratings: Option<SkillsetRatings>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+#[serde(default)]
+pub struct DifficultyTable {
+    pub version: String,
+    pub rates: Vec<RateDifficulty>,
+}
+
+impl DifficultyTable {
+    pub fn get(&self, rate: f32) -> Option<&RateDifficulty> {
+        self.rates.iter().find(|r| (r.rate - rate).abs() < 0.001)
+    }
+
+    pub fn from_string(input: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(input)?)
+    }
+
+    pub fn to_string(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+}
@@ -9,5 +9,6 @@ bitflags! {
         const Ring = 1 << 2;
         const Pinkie = 1 << 3;
         const Thumb = 1 << 4;
+        const Scratch = 1 << 5;
     }
 }
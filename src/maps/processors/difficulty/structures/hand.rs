@@ -1,7 +1,9 @@
-#[derive(Default, Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Default, Clone, Copy, Eq, PartialEq, Hash, Debug)]
 pub enum Hand {
     Left,
     #[default]
     Right,
     Ambiguous,
+    // The scratch lane, solved as its own hand so it doesn't chord or roll with the keys.
+    Scratch,
 }
@@ -0,0 +1,143 @@
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+
+use super::{FingerState, Hand};
+use crate::enums::quaver::{GameMode, LaneLayout};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LaneFingering {
+    pub hand: Hand,
+    pub finger: FingerState,
+}
+
+impl LaneFingering {
+    pub fn new(hand: Hand, finger: FingerState) -> Self {
+        Self { hand, finger }
+    }
+}
+
+// Which hand and finger plays each lane. A lane mapped to `None` is left out of the calculation.
+// Lanes on `Hand::Ambiguous` are solved once for each hand and the two results are averaged.
+// The scratch lane is on `Hand::Scratch` by default, but it can be played by either hand.
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct HandLayout {
+    pub lanes: BTreeMap<i32, Option<LaneFingering>>,
+}
+
+impl HandLayout {
+    // Lane N is played with the Nth fingering.
+    pub fn new(fingerings: &[(Hand, FingerState)]) -> Self {
+        Self {
+            lanes: fingerings
+                .iter()
+                .enumerate()
+                .map(|(i, &(hand, finger))| (i as i32 + 1, Some(LaneFingering::new(hand, finger))))
+                .collect(),
+        }
+    }
+
+    pub fn default_for(layout: LaneLayout) -> Self {
+        let self_ = match layout.mode() {
+            GameMode::Keys4 => Self::new(&[
+                (Hand::Left, FingerState::Middle),
+                (Hand::Left, FingerState::Index),
+                (Hand::Right, FingerState::Index),
+                (Hand::Right, FingerState::Middle),
+            ]),
+            GameMode::Keys7 => Self::keys7_thumb(Hand::Ambiguous),
+        };
+
+        match layout.scratch_lane() {
+            Some(lane) => self_.with_lane(lane, Hand::Scratch, FingerState::Scratch),
+            None => self_,
+        }
+    }
+
+    // 7K with the middle lane played by the thumb of the given hand.
+    pub fn keys7_thumb(hand: Hand) -> Self {
+        Self::new(&[
+            (Hand::Left, FingerState::Ring),
+            (Hand::Left, FingerState::Middle),
+            (Hand::Left, FingerState::Index),
+            (hand, FingerState::Thumb),
+            (Hand::Right, FingerState::Index),
+            (Hand::Right, FingerState::Middle),
+            (Hand::Right, FingerState::Ring),
+        ])
+    }
+
+    pub fn with_lane(mut self, lane: i32, hand: Hand, finger: FingerState) -> Self {
+        self.lanes
+            .insert(lane, Some(LaneFingering::new(hand, finger)));
+        self
+    }
+
+    pub fn with_skipped_lane(mut self, lane: i32) -> Self {
+        self.lanes.insert(lane, None);
+        self
+    }
+
+    pub fn fingering(&self, lane: i32) -> Option<LaneFingering> {
+        self.lanes.get(&lane).copied().flatten()
+    }
+
+    pub fn has_ambiguous_hand(&self) -> bool {
+        self.lanes
+            .values()
+            .flatten()
+            .any(|f| f.hand == Hand::Ambiguous)
+    }
+
+    pub fn validate(&self, layout: LaneLayout) -> Result<()> {
+        for lane in layout.lanes() {
+            match self.lanes.get(&lane) {
+                None => return Err(anyhow!("No hand mapping for lane {}", lane)),
+                Some(None) if !layout.is_scratch_lane(lane) => {
+                    return Err(anyhow!(
+                        "Only the scratch lane can be skipped, not lane {}",
+                        lane
+                    ))
+                }
+                Some(Some(fingering)) if fingering.finger.bits().count_ones() != 1 => {
+                    return Err(anyhow!(
+                        "Lane {} must be played by exactly one finger, got {:?}",
+                        lane,
+                        fingering.finger
+                    ))
+                }
+                Some(Some(fingering))
+                    if (fingering.hand == Hand::Scratch
+                        || fingering.finger == FingerState::Scratch)
+                        && !layout.is_scratch_lane(lane) =>
+                {
+                    return Err(anyhow!(
+                        "Only the scratch lane can be played as scratch, not lane {}",
+                        lane
+                    ))
+                }
+                Some(Some(fingering))
+                    if (fingering.hand == Hand::Scratch)
+                        != (fingering.finger == FingerState::Scratch) =>
+                {
+                    return Err(anyhow!(
+                        "Lane {} must use the scratch finger on the scratch hand, got {:?} on {:?}",
+                        lane,
+                        fingering.finger,
+                        fingering.hand
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(lane) = self.lanes.keys().find(|&&l| !layout.contains_lane(l)) {
+            return Err(anyhow!(
+                "Lane {} is outside of the {} key layout",
+                lane,
+                layout.key_count()
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -1,13 +1,21 @@
+mod difficulty_graph;
+mod difficulty_table;
 mod finger_action;
 mod finger_state;
 mod hand;
+mod hand_layout;
 mod ln_layer_type;
+mod skillset_ratings;
 mod strain_solver_data;
 mod strain_solver_hit_object;
 
+pub use difficulty_graph::*;
+pub use difficulty_table::*;
 pub use finger_action::*;
 pub use finger_state::*;
 pub use hand::*;
+pub use hand_layout::*;
 pub use ln_layer_type::*;
+pub use skillset_ratings::*;
 pub use strain_solver_data::*;
 pub use strain_solver_hit_object::*;
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use super::FingerAction;
+use crate::enums::quaver::QssPatternFlags;
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub enum Skillset {
+    Stream,
+    Jumpstream,
+    Handstream,
+    Jack,
+    Chordjack,
+    Technical,
+    LongNote,
+}
+
+impl Skillset {
+    pub const ALL: [Skillset; 7] = [
+        Skillset::Stream,
+        Skillset::Jumpstream,
+        Skillset::Handstream,
+        Skillset::Jack,
+        Skillset::Chordjack,
+        Skillset::Technical,
+        Skillset::LongNote,
+    ];
+
+    pub fn patterns(&self) -> QssPatternFlags {
+        match self {
+            Skillset::Stream => QssPatternFlags::LightStream,
+            Skillset::Jumpstream => {
+                QssPatternFlags::JumpStream
+                    | QssPatternFlags::JumpTrill
+                    | QssPatternFlags::SplitTrill
+            }
+            Skillset::Handstream => QssPatternFlags::HandStream | QssPatternFlags::QuadStream,
+            Skillset::Jack => QssPatternFlags::MiniJack | QssPatternFlags::LongJack,
+            Skillset::Chordjack => {
+                QssPatternFlags::ChordJack
+                    | QssPatternFlags::KoreaJack
+                    | QssPatternFlags::QuadJack
+                    | QssPatternFlags::ControlVibro
+            }
+            Skillset::Technical => QssPatternFlags::Polyrhythm | QssPatternFlags::SplitTrill,
+            Skillset::LongNote => QssPatternFlags::InverseLN | QssPatternFlags::ReleaseLN,
+        }
+    }
+
+    // Brackets don't show up as a row pattern, so technical sections are also found by action.
+    pub fn finger_actions(&self) -> &'static [FingerAction] {
+        match self {
+            Skillset::Technical => &[FingerAction::Bracket],
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+#[serde(default)]
+pub struct SkillsetRatings {
+    pub overall: f32,
+    pub stream: f32,
+    pub jumpstream: f32,
+    pub handstream: f32,
+    pub jack: f32,
+    pub chordjack: f32,
+    pub technical: f32,
+    pub long_note: f32,
+}
+
+impl SkillsetRatings {
+    pub fn get(&self, skillset: Skillset) -> f32 {
+        match skillset {
+            Skillset::Stream => self.stream,
+            Skillset::Jumpstream => self.jumpstream,
+            Skillset::Handstream => self.handstream,
+            Skillset::Jack => self.jack,
+            Skillset::Chordjack => self.chordjack,
+            Skillset::Technical => self.technical,
+            Skillset::LongNote => self.long_note,
+        }
+    }
+
+    pub fn set(&mut self, skillset: Skillset, rating: f32) {
+        match skillset {
+            Skillset::Stream => self.stream = rating,
+            Skillset::Jumpstream => self.jumpstream = rating,
+            Skillset::Handstream => self.handstream = rating,
+            Skillset::Jack => self.jack = rating,
+            Skillset::Chordjack => self.chordjack = rating,
+            Skillset::Technical => self.technical = rating,
+            Skillset::LongNote => self.long_note = rating,
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<(Skillset, f32)> {
+        Skillset::ALL.iter().map(|&s| (s, self.get(s))).collect()
+    }
+
+    pub fn strongest(&self) -> Option<Skillset> {
+        Skillset::ALL
+            .iter()
+            .copied()
+            .filter(|&s| self.get(s) > 0.)
+            .max_by(|&a, &b| self.get(a).total_cmp(&self.get(b)))
+    }
+}
@@ -36,7 +36,7 @@ impl StrainSolverData {
     }
 
     pub fn calculate_strain_value(&mut self) {
-        for mut hit_ob in self.hit_objects.iter_mut() {
+        for hit_ob in self.hit_objects.iter_mut() {
             hit_ob.strain_value = self.action_strain_coefficient
                 * self.pattern_strain_multiplier
                 * self.roll_manipulation_strain_multiplier
@@ -1,7 +1,9 @@
 pub mod difficulty;
+pub mod player_rating_processor;
 pub mod rating_processor;
 pub mod scoring;
 
 pub use difficulty::*;
+pub use player_rating_processor::*;
 pub use rating_processor::*;
 pub use scoring::*;
@@ -0,0 +1,164 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use super::RatingProcessor;
+use crate::enums::quaver::ModIdentifier;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerScore {
+    pub map_md5: String,
+    pub difficulty_rating: f32,
+    pub accuracy: f32,
+    pub mods: ModIdentifier,
+    pub failed: bool,
+    // Unix timestamp in milliseconds.
+    pub time: i64,
+}
+
+impl PlayerScore {
+    pub fn rating(&self) -> f32 {
+        if self.mods != ModIdentifier::None && self.mods.contains(ModIdentifier::Autoplay) {
+            return 0.;
+        }
+
+        RatingProcessor::new(self.difficulty_rating)
+            .calculate_rating(self.accuracy, Some(self.failed))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PlayerRatingHistoryPoint {
+    pub time: i64,
+    pub overall_rating: f32,
+    pub performance_points: f32,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct PlayerRatingProcessor {
+    pub scores: Vec<PlayerScore>,
+}
+
+impl PlayerRatingProcessor {
+    #[allow(dead_code)]
+    const VERSION: &'static str = "0.0.1";
+
+    const WEIGHT_DECAY: f32 = 0.95;
+    const QUAVER_TOP_SCORES: usize = 50;
+    const OSU_TOP_SCORES: usize = 100;
+
+    pub fn new(scores: Vec<PlayerScore>) -> Self {
+        Self { scores }
+    }
+
+    pub fn add_score(&mut self, score: PlayerScore) {
+        self.scores.push(score);
+    }
+
+    // The best rated score on each map, highest first.
+    pub fn best_scores(&self) -> Vec<&PlayerScore> {
+        Self::best_of(self.scores.iter())
+            .into_iter()
+            .map(|(_, s)| s)
+            .collect()
+    }
+
+    pub fn overall_rating(&self) -> f32 {
+        let ratings = Self::ratings(&Self::best_of(self.scores.iter()));
+        Self::weighted_sum(&ratings, Self::QUAVER_TOP_SCORES)
+    }
+
+    // osu! weighs the top 100 plays and adds a bonus for the number of ranked plays.
+    pub fn performance_points(&self) -> f32 {
+        let ratings = Self::ratings(&Self::best_of(self.scores.iter()));
+        Self::weighted_sum(&ratings, Self::OSU_TOP_SCORES)
+            + Self::bonus_performance_points(ratings.len())
+    }
+
+    // Ratings after each score in play order. The best score on each map is kept as scores come
+    // in, so only the score that was replaced has to move in the ranking.
+    pub fn history(&self) -> Vec<PlayerRatingHistoryPoint> {
+        let mut scores: Vec<&PlayerScore> = self.scores.iter().collect();
+        scores.sort_by_key(|s| s.time);
+
+        let mut best: HashMap<&str, (f32, i64)> = HashMap::new();
+        let mut ranking: Vec<(f32, i64)> = Vec::new();
+
+        scores
+            .into_iter()
+            .map(|score| {
+                let rated = (score.rating(), score.time);
+
+                match best.get(score.map_md5.as_str()) {
+                    Some(&previous) if previous.0 >= rated.0 => {}
+                    previous => {
+                        if let Some(&previous) = previous {
+                            let index = ranking
+                                .binary_search_by(|x| Self::compare(x, &previous))
+                                .unwrap();
+                            ranking.remove(index);
+                        }
+
+                        let index = ranking
+                            .binary_search_by(|x| Self::compare(x, &rated))
+                            .unwrap_or_else(|i| i);
+                        ranking.insert(index, rated);
+                        best.insert(score.map_md5.as_str(), rated);
+                    }
+                }
+
+                let ratings: Vec<f32> = ranking
+                    .iter()
+                    .take(Self::OSU_TOP_SCORES)
+                    .map(|x| x.0)
+                    .collect();
+
+                PlayerRatingHistoryPoint {
+                    time: score.time,
+                    overall_rating: Self::weighted_sum(&ratings, Self::QUAVER_TOP_SCORES),
+                    performance_points: Self::weighted_sum(&ratings, Self::OSU_TOP_SCORES)
+                        + Self::bonus_performance_points(ranking.len()),
+                }
+            })
+            .collect()
+    }
+
+    // Highest rating first, the earlier score first on a tie.
+    fn compare(a: &(f32, i64), b: &(f32, i64)) -> Ordering {
+        b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1))
+    }
+
+    fn best_of<'a>(scores: impl Iterator<Item = &'a PlayerScore>) -> Vec<(f32, &'a PlayerScore)> {
+        let mut best: HashMap<&str, (f32, &PlayerScore)> = HashMap::new();
+
+        for score in scores {
+            let rating = score.rating();
+            let entry = best
+                .entry(score.map_md5.as_str())
+                .or_insert((rating, score));
+            if rating > entry.0 {
+                *entry = (rating, score);
+            }
+        }
+
+        let mut best: Vec<(f32, &PlayerScore)> = best.into_values().collect();
+        best.sort_by(|a, b| Self::compare(&(a.0, a.1.time), &(b.0, b.1.time)));
+        best
+    }
+
+    fn ratings(scores: &[(f32, &PlayerScore)]) -> Vec<f32> {
+        scores.iter().map(|(rating, _)| *rating).collect()
+    }
+
+    fn weighted_sum(ratings: &[f32], top: usize) -> f32 {
+        ratings
+            .iter()
+            .take(top)
+            .enumerate()
+            .map(|(i, rating)| rating * Self::WEIGHT_DECAY.powi(i as i32))
+            .sum()
+    }
+
+    fn bonus_performance_points(score_count: usize) -> f32 {
+        416.6667 * (1. - 0.9994f32.powi(score_count as i32))
+    }
+}
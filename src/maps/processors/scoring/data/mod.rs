@@ -12,6 +12,8 @@ pub struct HitStat {
     pub hit_difference: i32,
     pub accuracy: f32,
     pub health: f32,
+    pub combo: i64,
+    pub score: i64,
 }
 
 impl HitStat {
@@ -35,6 +37,8 @@ impl HitStat {
             judgement: judgement.unwrap_or(Judgement::Ghost),
             accuracy: accuracy.unwrap_or(0.0),
             health: health.unwrap_or(0.0),
+            combo: 0,
+            score: 0,
         }
     }
 }
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+use crate::enums::osu;
+
+#[derive(Eq, PartialEq, PartialOrd, Ord, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Grade {
+    X,
+    SS,
+    S,
+    A,
+    B,
+    C,
+    D,
+    F,
+}
+
+impl Grade {
+    pub fn from_accuracy(accuracy: f32, failed: bool) -> Self {
+        if failed {
+            return Grade::F;
+        }
+
+        match accuracy {
+            a if a >= 100. => Grade::X,
+            a if a >= 99. => Grade::SS,
+            a if a >= 95. => Grade::S,
+            a if a >= 90. => Grade::A,
+            a if a >= 80. => Grade::B,
+            a if a >= 70. => Grade::C,
+            _ => Grade::D,
+        }
+    }
+}
+
+// SSH and SH are the silver grades awarded when Hidden, Fade In or Flashlight is active.
+#[derive(Eq, PartialEq, PartialOrd, Ord, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum OsuGrade {
+    SSH,
+    SS,
+    SH,
+    S,
+    A,
+    B,
+    C,
+    D,
+    F,
+}
+
+impl OsuGrade {
+    pub fn from_accuracy(accuracy: f32, mods: osu::ModIdentifier, failed: bool) -> Self {
+        if failed {
+            return OsuGrade::F;
+        }
+
+        let silver = mods.intersects(
+            osu::ModIdentifier::Hidden
+                | osu::ModIdentifier::FadeIn
+                | osu::ModIdentifier::Flashlight,
+        );
+
+        match accuracy {
+            a if a >= 100. && silver => OsuGrade::SSH,
+            a if a >= 100. => OsuGrade::SS,
+            a if a > 95. && silver => OsuGrade::SH,
+            a if a > 95. => OsuGrade::S,
+            a if a > 90. => OsuGrade::A,
+            a if a > 80. => OsuGrade::B,
+            a if a > 70. => OsuGrade::C,
+            _ => OsuGrade::D,
+        }
+    }
+}
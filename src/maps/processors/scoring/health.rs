@@ -0,0 +1,15 @@
+use super::judgement_windows::Judgement;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HealthSample {
+    pub time: i32,
+    pub object_index: Option<usize>,
+    pub judgement: Judgement,
+    pub health: f32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FailPoint {
+    pub time: i32,
+    pub object_index: Option<usize>,
+}
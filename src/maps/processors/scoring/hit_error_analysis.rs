@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use super::data::{HitStat, HitStatType, KeyPressType};
+
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+pub struct HitErrorDistribution {
+    pub count: usize,
+    pub mean: f32,
+    pub standard_deviation: f32,
+    pub unstable_rate: f32,
+}
+
+impl HitErrorDistribution {
+    pub fn from_offsets(offsets: &[f32]) -> Self {
+        if offsets.is_empty() {
+            return Self::default();
+        }
+
+        let count = offsets.len();
+        let mean = offsets.iter().sum::<f32>() / count as f32;
+        let variance = offsets.iter().map(|o| (o - mean).powi(2)).sum::<f32>() / count as f32;
+        let standard_deviation = variance.sqrt();
+
+        Self {
+            count,
+            mean,
+            standard_deviation,
+            unstable_rate: standard_deviation * 10.,
+        }
+    }
+}
+
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HitErrorHistogramBucket {
+    pub start: i32,
+    pub end: i32,
+    pub count: usize,
+}
+
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+pub struct HitErrorDriftPoint {
+    pub start_time: i32,
+    pub end_time: i32,
+    pub mean: f32,
+    pub count: usize,
+}
+
+// Hit differences follow Quaver's convention: object time minus song position, so a positive
+// value is an early hit and a negative value a late one.
+#[derive(Default, Clone)]
+pub struct HitErrorAnalysis {
+    pub hits: Vec<HitStat>,
+    pub overall: HitErrorDistribution,
+    pub press: HitErrorDistribution,
+    pub release: HitErrorDistribution,
+    pub lane_bias: BTreeMap<i32, HitErrorDistribution>,
+}
+
+impl HitErrorAnalysis {
+    pub fn new(stats: &[HitStat]) -> Self {
+        let mut hits: Vec<HitStat> = stats
+            .iter()
+            .filter(|s| s.type_ == HitStatType::HIT && s.hit_difference != i32::MIN)
+            .copied()
+            .collect();
+        hits.sort_by_key(|s| s.song_position);
+
+        let overall = HitErrorDistribution::from_offsets(&Self::offsets(&hits, None));
+        let press =
+            HitErrorDistribution::from_offsets(&Self::offsets(&hits, Some(KeyPressType::PRESS)));
+        let release =
+            HitErrorDistribution::from_offsets(&Self::offsets(&hits, Some(KeyPressType::RELEASE)));
+
+        let mut lane_offsets: BTreeMap<i32, Vec<f32>> = BTreeMap::new();
+        for hit in hits.iter() {
+            if let Some(hit_object) = hit.hit_object {
+                lane_offsets
+                    .entry(hit_object.lane)
+                    .or_default()
+                    .push(hit.hit_difference as f32);
+            }
+        }
+
+        let lane_bias = lane_offsets
+            .into_iter()
+            .map(|(lane, offsets)| (lane, HitErrorDistribution::from_offsets(&offsets)))
+            .collect();
+
+        Self {
+            hits,
+            overall,
+            press,
+            release,
+            lane_bias,
+        }
+    }
+
+    fn offsets(hits: &[HitStat], key_press_type: Option<KeyPressType>) -> Vec<f32> {
+        hits.iter()
+            .filter(|s| key_press_type.is_none_or(|t| s.key_press_type == t))
+            .map(|s| s.hit_difference as f32)
+            .collect()
+    }
+
+    pub fn early_count(&self) -> usize {
+        self.hits.iter().filter(|s| s.hit_difference > 0).count()
+    }
+
+    pub fn late_count(&self) -> usize {
+        self.hits.iter().filter(|s| s.hit_difference < 0).count()
+    }
+
+    // Buckets span `-max_offset..max_offset` in steps of `bucket_size`; offsets outside of the
+    // range are clamped into the outermost buckets.
+    pub fn histogram(&self, bucket_size: i32, max_offset: i32) -> Vec<HitErrorHistogramBucket> {
+        if bucket_size <= 0 || max_offset <= 0 {
+            return Vec::new();
+        }
+
+        let mut buckets: Vec<HitErrorHistogramBucket> = (-max_offset..max_offset)
+            .step_by(bucket_size as usize)
+            .map(|start| HitErrorHistogramBucket {
+                start,
+                end: (start + bucket_size).min(max_offset),
+                count: 0,
+            })
+            .collect();
+
+        for hit in self.hits.iter() {
+            let offset = hit.hit_difference.clamp(-max_offset, max_offset - 1);
+            let index = ((offset + max_offset) / bucket_size) as usize;
+            buckets[index].count += 1;
+        }
+
+        buckets
+    }
+
+    pub fn drift(&self, window_size: i32) -> Vec<HitErrorDriftPoint> {
+        let (first, last) = match (self.hits.first(), self.hits.last()) {
+            (Some(first), Some(last)) if window_size > 0 => {
+                (first.song_position, last.song_position)
+            }
+            _ => return Vec::new(),
+        };
+
+        let mut points: Vec<HitErrorDriftPoint> = Vec::new();
+        let mut hits = self.hits.iter().peekable();
+
+        for start_time in (first..=last).step_by(window_size as usize) {
+            let end_time = start_time + window_size;
+            let mut sum = 0.;
+            let mut count = 0;
+
+            while let Some(hit) = hits.next_if(|s| s.song_position < end_time) {
+                sum += hit.hit_difference as f32;
+                count += 1;
+            }
+
+            if count == 0 {
+                continue;
+            }
+
+            points.push(HitErrorDriftPoint {
+                start_time,
+                end_time,
+                mean: sum / count as f32,
+                count,
+            });
+        }
+
+        points
+    }
+}
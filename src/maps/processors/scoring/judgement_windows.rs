@@ -1,6 +1,24 @@
+use anyhow::Result;
 use num_derive::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
 
-#[derive(Eq, PartialEq, PartialOrd, Hash, Copy, Clone, Debug, FromPrimitive, ToPrimitive, Ord)]
+use super::OsuHitWindows;
+use crate::enums::{osu, quaver};
+
+#[derive(
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Hash,
+    Copy,
+    Clone,
+    Debug,
+    FromPrimitive,
+    ToPrimitive,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
 pub enum Judgement {
     Marv,
     Perf,
@@ -11,6 +29,9 @@ pub enum Judgement {
     Ghost,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+#[serde(default)]
 pub struct JudgementWindows {
     pub id: i32,
     pub name: String,
@@ -53,4 +74,126 @@ impl JudgementWindows {
             _ => None,
         }
     }
+
+    pub fn standard() -> Self {
+        Self {
+            name: "Standard".to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn strict() -> Self {
+        Self {
+            id: 1,
+            name: "Strict".to_string(),
+            is_default: false,
+            marvelous: 16.,
+            perfect: 39.,
+            great: 69.,
+            good: 96.,
+            okay: 115.,
+            miss: 148.,
+            ..Default::default()
+        }
+    }
+
+    pub fn chill() -> Self {
+        Self {
+            id: 2,
+            name: "Chill".to_string(),
+            is_default: false,
+            marvelous: 19.,
+            perfect: 47.,
+            great: 83.,
+            good: 117.,
+            okay: 140.,
+            miss: 180.,
+            ..Default::default()
+        }
+    }
+
+    // Strict and Chill move each window by the ratio between their preset and the standard set,
+    // so the standard windows become exactly the preset.
+    pub fn with_mods(&self, mods: quaver::ModIdentifier) -> Self {
+        let preset = if mods.contains(quaver::ModIdentifier::Strict) {
+            Self::strict()
+        } else if mods.contains(quaver::ModIdentifier::Chill) {
+            Self::chill()
+        } else {
+            return self.clone();
+        };
+
+        let standard = Self::standard();
+
+        Self {
+            marvelous: self.marvelous * preset.marvelous / standard.marvelous,
+            perfect: self.perfect * preset.perfect / standard.perfect,
+            great: self.great * preset.great / standard.great,
+            good: self.good * preset.good / standard.good,
+            okay: self.okay * preset.okay / standard.okay,
+            miss: self.miss * preset.miss / standard.miss,
+            ..self.clone()
+        }
+    }
+
+    // MAX, 300, 200, 100, 50 and miss map onto Marv through Miss.
+    pub fn from_osu_overall_difficulty(overall_difficulty: f32) -> Self {
+        let windows =
+            OsuHitWindows::from_overall_difficulty(overall_difficulty, osu::ModIdentifier::None);
+
+        Self {
+            id: 3 + (overall_difficulty * 10.).round() as i32,
+            name: format!("osu! OD{}", overall_difficulty),
+            is_default: false,
+            combo_break_judgement: Judgement::Miss,
+            marvelous: windows.max,
+            perfect: windows.hit300,
+            great: windows.hit200,
+            good: windows.hit100,
+            okay: windows.hit50,
+            miss: windows.miss,
+        }
+    }
+
+    pub fn presets() -> Vec<Self> {
+        let mut presets = vec![Self::standard(), Self::strict(), Self::chill()];
+        presets.extend((0..=10).map(|od| Self::from_osu_overall_difficulty(od as f32)));
+        presets
+    }
+
+    pub fn preset(name: &str) -> Option<Self> {
+        Self::presets()
+            .into_iter()
+            .find(|w| w.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn scaled(&self, multiplier: f32) -> Self {
+        Self {
+            marvelous: self.marvelous * multiplier,
+            perfect: self.perfect * multiplier,
+            great: self.great * multiplier,
+            good: self.good * multiplier,
+            okay: self.okay * multiplier,
+            miss: self.miss * multiplier,
+            ..self.clone()
+        }
+    }
+
+    pub fn from_path(path: &str) -> Result<Vec<Self>> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_yaml::from_reader(file)?)
+    }
+
+    pub fn from_string(input: &str) -> Result<Vec<Self>> {
+        Ok(serde_yaml::from_str(input)?)
+    }
+
+    pub fn to_string(sets: &[Self]) -> Result<String> {
+        Ok(serde_yaml::to_string(sets)?)
+    }
+
+    pub fn to_file(sets: &[Self], path: &str) -> Result<()> {
+        let w = std::fs::File::create(path)?;
+        Ok(serde_yaml::to_writer(w, sets)?)
+    }
 }
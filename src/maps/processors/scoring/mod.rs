@@ -1,7 +1,20 @@
 pub mod data;
+pub mod grade;
+pub mod health;
+pub mod hit_error_analysis;
 pub mod judgement_windows;
+pub mod osu_score_processor;
+mod replay_processing;
 pub mod score_processor;
+pub mod score_summary;
+pub mod wife_processor;
 
 pub use data::*;
+pub use grade::*;
+pub use health::*;
+pub use hit_error_analysis::*;
 pub use judgement_windows::*;
+pub use osu_score_processor::*;
 pub use score_processor::*;
+pub use score_summary::*;
+pub use wife_processor::*;
@@ -0,0 +1,506 @@
+use std::collections::BTreeMap;
+
+use super::OsuGrade;
+use crate::enums::osu::ModIdentifier;
+use crate::maps::parsers::{HitObjectType, OsuBeatmap};
+use crate::replays::osu::ReplayLaneEventType;
+use crate::replays::OsuReplay;
+
+#[derive(Eq, PartialEq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
+pub enum OsuJudgement {
+    Max,
+    Hit300,
+    Hit200,
+    Hit100,
+    Hit50,
+    Miss,
+}
+
+impl OsuJudgement {
+    pub fn hit_value(self) -> i32 {
+        match self {
+            OsuJudgement::Max => 320,
+            OsuJudgement::Hit300 => 300,
+            OsuJudgement::Hit200 => 200,
+            OsuJudgement::Hit100 => 100,
+            OsuJudgement::Hit50 => 50,
+            OsuJudgement::Miss => 0,
+        }
+    }
+
+    fn hit_bonus_value(self) -> f64 {
+        match self {
+            OsuJudgement::Max | OsuJudgement::Hit300 => 32.,
+            OsuJudgement::Hit200 => 16.,
+            OsuJudgement::Hit100 => 8.,
+            OsuJudgement::Hit50 => 4.,
+            OsuJudgement::Miss => 0.,
+        }
+    }
+
+    fn hit_bonus(self) -> f64 {
+        match self {
+            OsuJudgement::Max => 2.,
+            OsuJudgement::Hit300 => 1.,
+            OsuJudgement::Hit200 => -8.,
+            OsuJudgement::Hit100 => -24.,
+            OsuJudgement::Hit50 => -44.,
+            OsuJudgement::Miss => -100.,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OsuHitWindows {
+    pub max: f32,
+    pub hit300: f32,
+    pub hit200: f32,
+    pub hit100: f32,
+    pub hit50: f32,
+    pub miss: f32,
+}
+
+impl OsuHitWindows {
+    // Windows are in song time, so rate mods widen them just like in Quaver.
+    pub fn from_overall_difficulty(overall_difficulty: f32, mods: ModIdentifier) -> Self {
+        let od = overall_difficulty;
+        let mut windows = Self {
+            max: 16.,
+            hit300: 64. - 3. * od,
+            hit200: 97. - 3. * od,
+            hit100: 127. - 3. * od,
+            hit50: 151. - 3. * od,
+            miss: 188. - 3. * od,
+        };
+
+        if mods.contains(ModIdentifier::HardRock) {
+            windows = windows.scaled(1. / 1.4);
+        } else if mods.contains(ModIdentifier::Easy) {
+            windows = windows.scaled(1.4);
+        }
+
+        if mods.contains(ModIdentifier::DoubleTime) || mods.contains(ModIdentifier::Nightcore) {
+            windows = windows.scaled(1.5);
+        } else if mods.contains(ModIdentifier::HalfTime) {
+            windows = windows.scaled(0.75);
+        }
+
+        windows
+    }
+
+    pub fn scaled(&self, factor: f32) -> Self {
+        Self {
+            max: self.max * factor,
+            hit300: self.hit300 * factor,
+            hit200: self.hit200 * factor,
+            hit100: self.hit100 * factor,
+            hit50: self.hit50 * factor,
+            miss: self.miss * factor,
+        }
+    }
+
+    pub fn get_value_from_judgement(&self, j: OsuJudgement) -> f32 {
+        match j {
+            OsuJudgement::Max => self.max,
+            OsuJudgement::Hit300 => self.hit300,
+            OsuJudgement::Hit200 => self.hit200,
+            OsuJudgement::Hit100 => self.hit100,
+            OsuJudgement::Hit50 => self.hit50,
+            OsuJudgement::Miss => self.miss,
+        }
+    }
+
+    // Presses inside the miss window but outside the 50 window are misses; anything further
+    // away doesn't hit the note at all.
+    pub fn judge(&self, hit_difference: i32) -> Option<OsuJudgement> {
+        let absolute_difference = hit_difference.abs() as f32;
+
+        [
+            OsuJudgement::Max,
+            OsuJudgement::Hit300,
+            OsuJudgement::Hit200,
+            OsuJudgement::Hit100,
+            OsuJudgement::Hit50,
+            OsuJudgement::Miss,
+        ]
+        .into_iter()
+        .find(|&j| absolute_difference <= self.get_value_from_judgement(j))
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct ManiaNote {
+    column: i32,
+    start_time: i32,
+    end_time: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OsuScoreMismatch {
+    pub field: String,
+    pub expected: i64,
+    pub actual: i64,
+}
+
+pub struct OsuScoreProcessor {
+    pub mods: ModIdentifier,
+    pub windows: OsuHitWindows,
+    pub score: i64,
+    pub accuracy: f32,
+    pub combo: i64,
+    pub max_combo: i64,
+    pub current_judgements: BTreeMap<OsuJudgement, i32>,
+    pub failed: bool,
+    total_judgements: i32,
+    base_score: f64,
+    bonus_score: f64,
+    bonus: f64,
+}
+
+impl OsuScoreProcessor {
+    const MAX_SCORE: f64 = 1_000_000.;
+    const LONG_NOTE_TAIL_MULTIPLIER: f32 = 1.5;
+
+    pub fn from_beatmap(beatmap: &OsuBeatmap, mods: ModIdentifier) -> Self {
+        let total_judgements = Self::beatmap_notes(beatmap)
+            .iter()
+            .map(|n| {
+                if n.end_time.is_some() && mods.contains(ModIdentifier::ScoreV2) {
+                    2
+                } else {
+                    1
+                }
+            })
+            .sum();
+
+        Self {
+            mods,
+            windows: OsuHitWindows::from_overall_difficulty(beatmap.overall_difficulty, mods),
+            score: 0,
+            accuracy: 0.,
+            combo: 0,
+            max_combo: 0,
+            current_judgements: BTreeMap::from([
+                (OsuJudgement::Max, 0),
+                (OsuJudgement::Hit300, 0),
+                (OsuJudgement::Hit200, 0),
+                (OsuJudgement::Hit100, 0),
+                (OsuJudgement::Hit50, 0),
+                (OsuJudgement::Miss, 0),
+            ]),
+            failed: false,
+            total_judgements,
+            base_score: 0.,
+            bonus_score: 0.,
+            bonus: 100.,
+        }
+    }
+
+    pub fn score_v2(&self) -> bool {
+        self.mods.contains(ModIdentifier::ScoreV2)
+    }
+
+    fn mod_multiplier(&self) -> f64 {
+        let mut multiplier = 1.;
+
+        for m in [
+            ModIdentifier::Easy,
+            ModIdentifier::NoFail,
+            ModIdentifier::HalfTime,
+        ] {
+            if self.mods.contains(m) {
+                multiplier *= 0.5;
+            }
+        }
+
+        multiplier
+    }
+
+    pub fn total_judgement_count(&self) -> i32 {
+        self.current_judgements.values().sum()
+    }
+
+    // ScoreV1 judges a hold note once, from how far off both the head and the release were.
+    pub fn calculate_long_note_judgement(
+        &self,
+        head_difference: i32,
+        tail_difference: Option<i32>,
+    ) -> OsuJudgement {
+        let tail = match tail_difference {
+            Some(tail) => tail.abs() as f32,
+            None => return OsuJudgement::Hit50,
+        };
+        let head = head_difference.abs() as f32;
+        let w = &self.windows;
+
+        if head <= w.max * 1.2 && head + tail <= w.max * 2.4 {
+            OsuJudgement::Max
+        } else if head <= w.hit300 * 1.1 && head + tail <= w.hit300 * 2.2 {
+            OsuJudgement::Hit300
+        } else if head <= w.hit200 && head + tail <= w.hit200 * 2. {
+            OsuJudgement::Hit200
+        } else if head <= w.hit100 && head + tail <= w.hit100 * 2. {
+            OsuJudgement::Hit100
+        } else {
+            OsuJudgement::Hit50
+        }
+    }
+
+    // ScoreV2 judges the release of a hold note on its own, with more lenient windows.
+    pub fn calculate_long_note_tail_judgement(&self, tail_difference: i32) -> OsuJudgement {
+        self.windows
+            .scaled(Self::LONG_NOTE_TAIL_MULTIPLIER)
+            .judge(tail_difference)
+            .unwrap_or(OsuJudgement::Miss)
+    }
+
+    pub fn calculate_score_from_judgement(&mut self, judgement: OsuJudgement) {
+        *self.current_judgements.entry(judgement).or_insert(0) += 1;
+
+        if judgement == OsuJudgement::Miss {
+            self.combo = 0;
+        } else {
+            self.combo += 1;
+            self.max_combo = self.max_combo.max(self.combo);
+        }
+
+        self.accuracy = self.calculate_accuracy();
+
+        if self.total_judgements == 0 {
+            return;
+        }
+
+        if self.score_v2() {
+            let progress = self.total_judgement_count() as f64 / self.total_judgements as f64;
+            let combo_portion = self.max_combo as f64 / self.total_judgements as f64;
+
+            self.score = (Self::MAX_SCORE
+                * self.mod_multiplier()
+                * (0.99 * (self.calculate_accuracy_v2() as f64 / 100.) * progress
+                    + 0.01 * combo_portion))
+                .round() as i64;
+        } else {
+            let note_value =
+                Self::MAX_SCORE * self.mod_multiplier() * 0.5 / self.total_judgements as f64;
+
+            self.bonus = (self.bonus + judgement.hit_bonus()).clamp(0., 100.);
+            self.base_score += note_value * judgement.hit_value() as f64 / 320.;
+            self.bonus_score += note_value * judgement.hit_bonus_value() * self.bonus.sqrt() / 320.;
+
+            self.score = (self.base_score + self.bonus_score).round() as i64;
+        }
+    }
+
+    fn weighted_accuracy(&self, max_weight: f32) -> f32 {
+        let total = self.total_judgement_count();
+
+        if total == 0 {
+            return 0.;
+        }
+
+        let mut points = 0.;
+        for (&judgement, &count) in self.current_judgements.iter() {
+            let weight = if judgement == OsuJudgement::Max {
+                max_weight
+            } else {
+                judgement.hit_value().min(300) as f32
+            };
+            points += weight * count as f32;
+        }
+
+        points / (total as f32 * max_weight) * 100.
+    }
+
+    pub fn calculate_accuracy(&self) -> f32 {
+        self.weighted_accuracy(300.)
+    }
+
+    pub fn grade(&self) -> OsuGrade {
+        OsuGrade::from_accuracy(self.calculate_accuracy(), self.mods, self.failed)
+    }
+
+    // ScoreV2 rewards MAX judgements over 300s.
+    pub fn calculate_accuracy_v2(&self) -> f32 {
+        self.weighted_accuracy(305.)
+    }
+
+    fn beatmap_notes(beatmap: &OsuBeatmap) -> Vec<ManiaNote> {
+        let key_count = beatmap.key_count.max(1);
+
+        let mut notes: Vec<ManiaNote> = beatmap
+            .hit_objects
+            .iter()
+            .map(|hit_object| ManiaNote {
+                column: (hit_object.x as f64 / (512f64 / key_count as f64))
+                    .clamp(0., (key_count - 1) as f64) as i32
+                    + 1,
+                start_time: hit_object.start_time,
+                end_time: hit_object
+                    .type_
+                    .contains(HitObjectType::Hold)
+                    .then_some(hit_object.end_time),
+            })
+            .collect();
+
+        notes.sort_by_key(|n| (n.start_time, n.column));
+        notes
+    }
+
+    // Hit differences here are press time minus object time. Columns are judged one at a time,
+    // then the judgements are applied in the order they happened so combo and bonus match.
+    pub fn process_replay(&mut self, beatmap: &OsuBeatmap, replay: &OsuReplay) {
+        let notes = Self::beatmap_notes(beatmap);
+        let key_count = beatmap.key_count;
+
+        let mut columns: BTreeMap<i32, Vec<ManiaNote>> = BTreeMap::new();
+        for note in notes {
+            columns.entry(note.column).or_default().push(note);
+        }
+
+        let events = replay.lane_events(key_count);
+        let mut judgements: Vec<(i32, OsuJudgement)> = Vec::new();
+
+        for (column, notes) in columns {
+            let mut index = 0;
+            let mut held: Option<(ManiaNote, i32)> = None;
+
+            for event in events.iter().filter(|e| e.lane == column) {
+                let time = event.time as i32;
+
+                match event.type_ {
+                    ReplayLaneEventType::Press => {
+                        while index < notes.len()
+                            && notes[index].start_time + (self.windows.miss as i32) < time
+                        {
+                            self.judge_unhit(&mut judgements, &notes[index]);
+                            index += 1;
+                        }
+
+                        let note = match notes.get(index) {
+                            Some(note) => *note,
+                            None => continue,
+                        };
+
+                        let head_difference = time - note.start_time;
+                        let judgement = match self.windows.judge(head_difference) {
+                            Some(judgement) => judgement,
+                            None => continue,
+                        };
+
+                        index += 1;
+
+                        if note.end_time.is_none() || judgement == OsuJudgement::Miss {
+                            self.judge_without_hold(&mut judgements, time, &note, judgement);
+                            continue;
+                        }
+
+                        if self.score_v2() {
+                            judgements.push((time, judgement));
+                        }
+
+                        held = Some((note, head_difference));
+                    }
+                    ReplayLaneEventType::Release => {
+                        if let Some((note, head_difference)) = held.take() {
+                            let judgement =
+                                self.judge_release(head_difference, time - note.end_time.unwrap());
+                            judgements.push((time, judgement));
+                        }
+                    }
+                }
+            }
+
+            if let Some((note, head_difference)) = held {
+                let judgement = if self.score_v2() {
+                    OsuJudgement::Miss
+                } else {
+                    self.calculate_long_note_judgement(head_difference, None)
+                };
+                judgements.push((note.end_time.unwrap(), judgement));
+            }
+
+            for note in notes.iter().skip(index) {
+                self.judge_unhit(&mut judgements, note);
+            }
+        }
+
+        judgements.sort_by_key(|&(time, _)| time);
+
+        for (_, judgement) in judgements {
+            self.calculate_score_from_judgement(judgement);
+        }
+
+        // osu! only stores health in the life bar, so that's where a failed play shows up.
+        self.failed = !self.mods.contains(ModIdentifier::NoFail)
+            && replay
+                .life_bar_graph()
+                .map(|graph| graph.reached_zero())
+                .unwrap_or(false);
+    }
+
+    fn judge_release(&self, head_difference: i32, tail_difference: i32) -> OsuJudgement {
+        if self.score_v2() {
+            self.calculate_long_note_tail_judgement(tail_difference)
+        } else {
+            self.calculate_long_note_judgement(head_difference, Some(tail_difference))
+        }
+    }
+
+    // Judges a note that won't be held: taps, and hold notes whose head was missed.
+    fn judge_without_hold(
+        &self,
+        judgements: &mut Vec<(i32, OsuJudgement)>,
+        time: i32,
+        note: &ManiaNote,
+        judgement: OsuJudgement,
+    ) {
+        judgements.push((time, judgement));
+
+        if let Some(end_time) = note.end_time {
+            if self.score_v2() {
+                judgements.push((end_time.max(time), OsuJudgement::Miss));
+            }
+        }
+    }
+
+    // Notes nobody pressed are missed once their miss window has passed.
+    fn judge_unhit(&self, judgements: &mut Vec<(i32, OsuJudgement)>, note: &ManiaNote) {
+        let time = note.start_time + self.windows.miss as i32;
+        self.judge_without_hold(judgements, time, note, OsuJudgement::Miss);
+    }
+
+    pub fn validate_against_replay(&self, replay: &OsuReplay) -> Vec<OsuScoreMismatch> {
+        let counts = [
+            ("count_geki", replay.count_geki as i64, OsuJudgement::Max),
+            ("count_300", replay.count_300 as i64, OsuJudgement::Hit300),
+            ("count_katu", replay.count_katu as i64, OsuJudgement::Hit200),
+            ("count_100", replay.count_100 as i64, OsuJudgement::Hit100),
+            ("count_50", replay.count_50 as i64, OsuJudgement::Hit50),
+            ("count_miss", replay.count_miss as i64, OsuJudgement::Miss),
+        ];
+
+        let mut mismatches: Vec<OsuScoreMismatch> = counts
+            .iter()
+            .map(|&(field, expected, judgement)| OsuScoreMismatch {
+                field: field.to_string(),
+                expected,
+                actual: self.current_judgements[&judgement] as i64,
+            })
+            .collect();
+
+        mismatches.push(OsuScoreMismatch {
+            field: "max_combo".to_string(),
+            expected: replay.max_combo as i64,
+            actual: self.max_combo,
+        });
+
+        mismatches.push(OsuScoreMismatch {
+            field: "score".to_string(),
+            expected: replay.score as i64,
+            actual: self.score,
+        });
+
+        mismatches.retain(|m| m.expected != m.actual);
+        mismatches
+    }
+}
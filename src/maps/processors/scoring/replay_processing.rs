@@ -0,0 +1,189 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use super::{
+    data::KeyPressType,
+    judgement_windows::{Judgement, JudgementWindows},
+    ScoreProcessor,
+};
+use crate::{
+    enums::quaver::ModIdentifier,
+    maps::QuaverMap,
+    replays::{quaver::ReplayKeyPressState, QuaverReplay},
+};
+
+impl ScoreProcessor {
+    pub fn from_map_and_replay(
+        map: &QuaverMap,
+        replay: &QuaverReplay,
+        windows: Option<JudgementWindows>,
+    ) -> Self {
+        Self::what_if(map, replay, windows, replay.mods)
+    }
+
+    // Judges the replay again as if it had been played with `mods`.
+    pub fn what_if(
+        map: &QuaverMap,
+        replay: &QuaverReplay,
+        windows: Option<JudgementWindows>,
+        mods: ModIdentifier,
+    ) -> Self {
+        let mut self_ = Self::from_map(map, mods, windows);
+        self_.player_name = replay.player_name.clone();
+        self_.date = replay.date.clone();
+        self_.process_replay(replay);
+        self_
+    }
+
+    pub fn without_no_fail(
+        map: &QuaverMap,
+        replay: &QuaverReplay,
+        windows: Option<JudgementWindows>,
+    ) -> Self {
+        let mods = if replay.mods == ModIdentifier::None {
+            ModIdentifier::empty()
+        } else {
+            replay.mods - ModIdentifier::NoFail
+        };
+
+        Self::what_if(map, replay, windows, mods)
+    }
+
+    // Plays the replay frames back against the map, judging presses, releases and misses in the
+    // same order the game would.
+    pub fn process_replay(&mut self, replay: &QuaverReplay) {
+        let mut order: Vec<usize> = (0..self.map.hit_objects.len()).collect();
+        order.sort_by_key(|&i| self.map.hit_objects[i].start_time);
+
+        let mut pending: BTreeMap<i32, VecDeque<usize>> = BTreeMap::new();
+        for i in order {
+            pending
+                .entry(self.map.hit_objects[i].lane)
+                .or_default()
+                .push_back(i);
+        }
+
+        let mut held: HashMap<i32, usize> = HashMap::new();
+        let mut previous = ReplayKeyPressState::empty();
+
+        for frame in replay.frames.iter() {
+            self.process_misses(&mut pending, &mut held, frame.time);
+
+            let pressed = frame.keys - previous;
+            let released = previous - frame.keys;
+            previous = frame.keys;
+
+            self.song_position = frame.time;
+
+            for lane in released.as_lanes() {
+                if let Some(index) = held.remove(&lane) {
+                    self.current_object = Some(index);
+
+                    let hit_difference = self.map.hit_objects[index].end_time - frame.time;
+                    let mut judgement = self.calculate_score_from_hit_difference(
+                        hit_difference,
+                        KeyPressType::RELEASE,
+                        Some(false),
+                    );
+
+                    // Letting go before the release window counts as an Okay.
+                    if judgement == Judgement::Ghost {
+                        judgement = Judgement::Okay;
+                    }
+
+                    self.calculate_score_from_hit(judgement, hit_difference, KeyPressType::RELEASE);
+                }
+            }
+
+            for lane in pressed.as_lanes() {
+                let index = match pending.get(&lane).and_then(|q| q.front()) {
+                    Some(&index) => index,
+                    None => continue,
+                };
+
+                self.current_object = Some(index);
+
+                let hit_object = self.map.hit_objects[index];
+                let judgement = self.calculate_score_from_hit_difference(
+                    hit_object.start_time - frame.time,
+                    KeyPressType::PRESS,
+                    None,
+                );
+
+                if judgement == Judgement::Ghost {
+                    continue;
+                }
+
+                pending.get_mut(&lane).unwrap().pop_front();
+
+                if hit_object.is_long_note() {
+                    if judgement == Judgement::Miss {
+                        self.calculate_score_from_judgement(Judgement::Miss, Some(true));
+                    } else {
+                        held.insert(lane, index);
+                    }
+                }
+            }
+        }
+
+        self.process_misses(&mut pending, &mut held, i32::MAX);
+        self.current_object = None;
+    }
+
+    fn process_misses(
+        &mut self,
+        pending: &mut BTreeMap<i32, VecDeque<usize>>,
+        held: &mut HashMap<i32, usize>,
+        time: i32,
+    ) {
+        let miss_window = self.judgement_window[&Judgement::Miss];
+        let release_window = self.judgement_window[&Judgement::Okay]
+            * Self::windows_release_multiplier()[&Judgement::Okay];
+
+        let mut missed: Vec<(i32, usize, bool)> = Vec::new();
+
+        for queue in pending.values_mut() {
+            while let Some(&index) = queue.front() {
+                let hit_object = self.map.hit_objects[index];
+                if hit_object.start_time as f32 + miss_window >= time as f32 {
+                    break;
+                }
+
+                queue.pop_front();
+                missed.push((
+                    (hit_object.start_time as f32 + miss_window) as i32,
+                    index,
+                    false,
+                ));
+            }
+        }
+
+        // Holding past the release window ends the long note with an Okay.
+        held.retain(|_, &mut index| {
+            let end = self.map.hit_objects[index].end_time as f32 + release_window;
+            if end >= time as f32 {
+                return true;
+            }
+
+            missed.push((end as i32, index, true));
+            false
+        });
+
+        missed.sort_by_key(|&(time, index, _)| (time, index));
+
+        for (time, index, overheld) in missed {
+            self.song_position = time;
+            self.current_object = Some(index);
+
+            if overheld {
+                self.calculate_score_from_judgement(Judgement::Okay, Some(true));
+                continue;
+            }
+
+            self.calculate_score_from_judgement(Judgement::Miss, Some(false));
+
+            if self.map.hit_objects[index].is_long_note() {
+                self.calculate_score_from_judgement(Judgement::Miss, Some(true));
+            }
+        }
+    }
+}
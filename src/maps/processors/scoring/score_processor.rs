@@ -4,7 +4,9 @@ use std::collections::{BTreeMap, HashMap};
 use crate::{enums::quaver::ModIdentifier, maps::QuaverMap, replays::QuaverReplay};
 
 use super::{
-    data::{HitStat, KeyPressType},
+    data::{HitStat, HitStatType, KeyPressType},
+    grade::Grade,
+    health::{FailPoint, HealthSample},
     judgement_windows::{Judgement, JudgementWindows},
 };
 
@@ -19,6 +21,10 @@ pub struct ScoreProcessor {
     pub max_combo: i64,
     pub windows: JudgementWindows,
     pub stats: Vec<HitStat>,
+    pub health_timeline: Vec<HealthSample>,
+    // Position and index of the object being judged, set by whatever is driving the processor.
+    pub song_position: i32,
+    pub current_object: Option<usize>,
     force_fail: bool,
     total_judgements: i32,
     pub summed_score: i64,
@@ -85,15 +91,86 @@ impl ScoreProcessor {
     const MULTIPLIER_MAX_INDEX: i32 = 15;
     const MULTIPLIER_COUNT_TO_INCREASE_INDEX: i32 = 10;
     const STANDARDIZED_MAX_SCORE: i32 = 1_000_000;
+    const HEALTH_ADJUST_DRAIN_MULTIPLIER: f32 = 0.5;
 
     pub fn failed(&self) -> bool {
-        self.health <= 0. && !self.mods.contains(ModIdentifier::NoFail) || self.force_fail
+        (self.health <= 0. || self.fail_point().is_some())
+            && !self.mods.contains(ModIdentifier::NoFail)
+            || self.force_fail
     }
 
     pub fn force_fail(&self) -> bool {
         self.force_fail
     }
 
+    pub fn grade(&self) -> Grade {
+        Grade::from_accuracy(self.accuracy, self.failed())
+    }
+
+    pub fn rate(&self) -> f32 {
+        self.mods.rate()
+    }
+
+    // The first time health hit zero, whether or not NoFail kept the play going.
+    pub fn fail_point(&self) -> Option<FailPoint> {
+        self.health_timeline
+            .iter()
+            .find(|s| s.health <= 0.)
+            .map(|s| FailPoint {
+                time: s.time,
+                object_index: s.object_index,
+            })
+    }
+
+    // A hit difference of `i32::MIN` means the object was never hit.
+    fn record_judgement(
+        &mut self,
+        judgement: Judgement,
+        hit_difference: i32,
+        key_press_type: KeyPressType,
+    ) {
+        let type_ = if hit_difference == i32::MIN && judgement == Judgement::Miss {
+            HitStatType::MISS
+        } else {
+            HitStatType::HIT
+        };
+
+        let mut stat = HitStat::new(
+            type_,
+            key_press_type,
+            self.current_object
+                .and_then(|i| self.map.hit_objects.get(i))
+                .copied(),
+            Some(self.song_position),
+            Some(judgement),
+            hit_difference,
+            Some(self.accuracy),
+            Some(self.health),
+        );
+        stat.combo = self.combo;
+        stat.score = self.score;
+        self.stats.push(stat);
+
+        self.health_timeline.push(HealthSample {
+            time: self.song_position,
+            object_index: self.current_object,
+            judgement,
+            health: self.health,
+        });
+    }
+
+    pub fn combo_curve(&self) -> impl Iterator<Item = (i32, i64)> + '_ {
+        self.stats.iter().map(|s| (s.song_position, s.combo))
+    }
+
+    pub fn accuracy_curve(&self) -> impl Iterator<Item = (i32, f32)> + '_ {
+        self.stats.iter().map(|s| (s.song_position, s.accuracy))
+    }
+
+    pub fn score_curve(&self) -> impl Iterator<Item = (i32, i64)> + '_ {
+        self.stats.iter().map(|s| (s.song_position, s.score))
+    }
+
     fn max_multiplier_count() -> i64 {
         (Self::MULTIPLIER_MAX_INDEX * Self::MULTIPLIER_COUNT_TO_INCREASE_INDEX) as i64
     }
@@ -114,7 +191,7 @@ impl ScoreProcessor {
         let map_clone = map.clone();
         let mut self_ = Self {
             map: map_clone,
-            mods,
+            mods: Self::normalise_mods(mods),
             health: 100.,
             ..Default::default()
         };
@@ -131,13 +208,14 @@ impl ScoreProcessor {
         self_.total_judgements = self_.get_total_judgement_count();
         self_.summed_score = self_.calculate_summed_score();
         self_.initialise_health_weighting();
+        self_.initialise_health_adjust();
         self_
     }
 
     pub fn from_replay(replay: &QuaverReplay, windows: Option<JudgementWindows>) -> Self {
         let mut self_ = Self {
             health: 100.,
-            mods: replay.mods,
+            mods: Self::normalise_mods(replay.mods),
             score: replay.score as i64,
             accuracy: replay.accuracy,
             max_combo: replay.max_combo as i64,
@@ -171,12 +249,17 @@ impl ScoreProcessor {
         self_
     }
 
+    // `ModIdentifier::None` is stored as -1, which would otherwise set every mod.
+    fn normalise_mods(mods: ModIdentifier) -> ModIdentifier {
+        if mods == ModIdentifier::None {
+            ModIdentifier::empty()
+        } else {
+            mods
+        }
+    }
+
     fn initialise_judgement_windows(&mut self, windows: Option<JudgementWindows>) {
-        self.windows = windows.unwrap_or(JudgementWindows {
-            name: "Standard".to_string(),
-            is_default: true,
-            ..Default::default()
-        });
+        self.windows = windows.unwrap_or_else(JudgementWindows::standard);
         self.judgement_window
             .insert(Judgement::Marv, self.windows.marvelous);
         self.judgement_window
@@ -192,9 +275,13 @@ impl ScoreProcessor {
     }
 
     fn initialise_mods(&mut self) {
-        let rate = self.mods.rate();
+        let windows = self.windows.with_mods(self.mods);
+        self.initialise_judgement_windows(Some(windows));
+
+        let multiplier = self.mods.rate();
+
         for window in self.judgement_window.values_mut() {
-            *window *= rate;
+            *window *= multiplier;
         }
     }
 
@@ -207,6 +294,10 @@ impl ScoreProcessor {
         let calculate_all_stats = calculate_all_stats.unwrap_or(true);
 
         if hit_difference == i32::MIN {
+            if calculate_all_stats {
+                self.calculate_score_from_hit(Judgement::Miss, hit_difference, key_press_type);
+            }
+
             return Judgement::Miss;
         }
 
@@ -245,22 +336,34 @@ impl ScoreProcessor {
         }
 
         if calculate_all_stats {
-            self.calculate_score_from_judgement(
-                judgement,
-                Some(key_press_type == KeyPressType::RELEASE),
-            );
+            self.calculate_score_from_hit(judgement, hit_difference, key_press_type);
         }
 
         judgement
     }
 
+    // For judgements that don't come from a press or release, like objects that were never hit.
     pub fn calculate_score_from_judgement(
         &mut self,
         judgement: Judgement,
         is_long_note_release: Option<bool>,
     ) {
-        let _is_long_note_release = is_long_note_release.unwrap_or(false);
+        let key_press_type = if is_long_note_release.unwrap_or(false) {
+            KeyPressType::RELEASE
+        } else {
+            KeyPressType::NONE
+        };
+
+        self.calculate_score_from_hit(judgement, i32::MIN, key_press_type);
+    }
 
+    // Applies a judgement and records the press or release it came from in `stats`.
+    pub fn calculate_score_from_hit(
+        &mut self,
+        judgement: Judgement,
+        hit_difference: i32,
+        key_press_type: KeyPressType,
+    ) {
         *self.current_judgements.entry(judgement).or_insert(0) += 1;
         self.accuracy = self.calculate_accuracy();
 
@@ -294,6 +397,7 @@ impl ScoreProcessor {
             if self.mods.contains(ModIdentifier::NoMiss) {
                 self.health = 0.;
                 self.force_fail = true;
+                self.record_judgement(judgement, hit_difference, key_press_type);
                 return;
             }
         }
@@ -315,6 +419,7 @@ impl ScoreProcessor {
         // Health calculation.
         self.health += self.judgement_health_weighting()[&judgement];
         self.health = self.health.clamp(0., 100.);
+        self.record_judgement(judgement, hit_difference, key_press_type);
     }
 
     fn calculate_accuracy(&self) -> f32 {
@@ -393,4 +498,18 @@ impl ScoreProcessor {
                 .insert(*key, (multiplier * weight * 100.).round() / 100.);
         }
     }
+
+    // Quaver never applies HeatlthAdjust. Here it softens every judgement that drains health, on
+    // top of the density weighting.
+    fn initialise_health_adjust(&mut self) {
+        if !self.mods.contains(ModIdentifier::HeatlthAdjust) {
+            return;
+        }
+
+        for weight in self.judgement_health_weighting().values_mut() {
+            if *weight < 0. {
+                *weight *= Self::HEALTH_ADJUST_DRAIN_MULTIPLIER;
+            }
+        }
+    }
 }
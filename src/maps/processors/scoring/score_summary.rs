@@ -0,0 +1,71 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{Grade, Judgement, ScoreProcessor};
+use crate::{enums::quaver::ModIdentifier, maps::processors::RatingProcessor};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+#[serde(default)]
+pub struct ScoreSummary {
+    pub player_name: String,
+    pub score: i64,
+    pub accuracy: f32,
+    pub max_combo: i64,
+    pub judgements: BTreeMap<Judgement, i32>,
+    pub rating: f32,
+    pub grade: Grade,
+    pub failed: bool,
+    // Stored as the raw bits, as in Quaver replays.
+    pub mods: i64,
+    pub rate: f32,
+}
+
+impl Default for ScoreSummary {
+    fn default() -> Self {
+        Self {
+            player_name: String::default(),
+            score: 0,
+            accuracy: 0.,
+            max_combo: 0,
+            judgements: BTreeMap::new(),
+            rating: 0.,
+            grade: Grade::D,
+            failed: false,
+            mods: 0,
+            rate: 1.,
+        }
+    }
+}
+
+impl ScoreSummary {
+    pub fn new(processor: &ScoreProcessor, rating_processor: &RatingProcessor) -> Self {
+        let failed = processor.failed();
+
+        Self {
+            player_name: processor.player_name.clone(),
+            score: processor.score,
+            accuracy: processor.accuracy,
+            max_combo: processor.max_combo,
+            judgements: processor.current_judgements.clone(),
+            rating: rating_processor.calculate_rating(processor.accuracy, Some(failed)),
+            grade: processor.grade(),
+            failed,
+            mods: processor.mods.bits(),
+            rate: processor.rate(),
+        }
+    }
+
+    pub fn mods(&self) -> ModIdentifier {
+        ModIdentifier::from_bits_truncate(self.mods)
+    }
+
+    pub fn from_string(input: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(input)?)
+    }
+
+    pub fn to_string(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+}
@@ -0,0 +1,236 @@
+use super::{
+    data::{HitStat, HitStatType, KeyPressType},
+    judgement_windows::JudgementWindows,
+    ScoreProcessor,
+};
+use crate::{enums::quaver::ModIdentifier, maps::QuaverMap, replays::QuaverReplay};
+
+// Etterna's Wife3 accuracy model. Offsets follow `ScoreProcessor`: object time minus song
+// position, in song time.
+#[derive(Default, Debug, Clone)]
+pub struct WifeProcessor {
+    pub mods: ModIdentifier,
+    pub judge: i32,
+    pub points: f32,
+    pub accuracy: f32,
+    pub judged_notes: i32,
+    pub total_notes: i32,
+    pub misses: i32,
+    pub hold_drops: i32,
+    pub mine_hits: i32,
+}
+
+impl WifeProcessor {
+    #[allow(dead_code)]
+    const VERSION: &'static str = "0.0.1";
+
+    // Timing scales for J1 through J9.
+    pub const JUDGE_SCALES: [f32; 9] = [1.50, 1.33, 1.16, 1.00, 0.84, 0.66, 0.50, 0.33, 0.20];
+    // Etterna's J4 windows from Marvelous to Bad.
+    const J4_WINDOWS: [f32; 5] = [22.5, 45., 90., 135., 180.];
+
+    const MAX_POINTS: f32 = 2.;
+    const MISS_WEIGHT: f32 = -5.5;
+    // Offset at J4 where a hit is worth a full miss, scaled with the judge like the other windows.
+    const MAX_BOO_WEIGHT: f32 = 180.;
+    const HOLD_DROP_WEIGHT: f32 = -4.5;
+    const MINE_HIT_WEIGHT: f32 = -7.;
+    const JUDGE_POWER: f32 = 0.75;
+
+    pub fn from_map(
+        map: &QuaverMap,
+        mods: ModIdentifier,
+        windows: Option<JudgementWindows>,
+    ) -> Self {
+        let windows = windows.unwrap_or_else(JudgementWindows::standard);
+
+        Self {
+            mods: if mods == ModIdentifier::None {
+                ModIdentifier::empty()
+            } else {
+                mods
+            },
+            judge: Self::judge_from_windows(&windows),
+            total_notes: map.hit_objects.len() as i32,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_hit_stats(
+        map: &QuaverMap,
+        mods: ModIdentifier,
+        windows: Option<JudgementWindows>,
+        stats: &[HitStat],
+    ) -> Self {
+        let mut self_ = Self::from_map(map, mods, windows);
+
+        for stat in stats.iter() {
+            // Holding a long note past its end has no offset but isn't a drop either.
+            if stat.key_press_type == KeyPressType::RELEASE
+                && stat.hit_difference == i32::MIN
+                && stat.type_ == HitStatType::HIT
+            {
+                continue;
+            }
+
+            self_.calculate_score_from_hit_difference(stat.hit_difference, stat.key_press_type);
+        }
+
+        self_
+    }
+
+    pub fn from_map_and_replay(
+        map: &QuaverMap,
+        replay: &QuaverReplay,
+        windows: Option<JudgementWindows>,
+    ) -> Self {
+        let processor = ScoreProcessor::from_map_and_replay(map, replay, windows.clone());
+        Self::from_hit_stats(map, replay.mods, windows, &processor.stats)
+    }
+
+    // Picks the judge whose timing scale is closest to how much wider or narrower the windows are
+    // than Etterna's J4, averaged over Marvelous through Okay.
+    pub fn judge_from_windows(windows: &JudgementWindows) -> i32 {
+        let ratios = [
+            windows.marvelous,
+            windows.perfect,
+            windows.great,
+            windows.good,
+            windows.okay,
+        ]
+        .iter()
+        .zip(Self::J4_WINDOWS.iter())
+        .map(|(window, j4)| window / j4)
+        .collect::<Vec<f32>>();
+
+        let timing_scale = ratios.iter().sum::<f32>() / ratios.len() as f32;
+
+        Self::JUDGE_SCALES
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                (*a - timing_scale)
+                    .abs()
+                    .total_cmp(&(*b - timing_scale).abs())
+            })
+            .map(|(i, _)| i as i32 + 1)
+            .unwrap()
+    }
+
+    pub fn set_judge(&mut self, judge: i32) {
+        self.judge = judge.clamp(1, Self::JUDGE_SCALES.len() as i32);
+    }
+
+    pub fn timing_scale(&self) -> f32 {
+        Self::JUDGE_SCALES[(self.judge.clamp(1, Self::JUDGE_SCALES.len() as i32) - 1) as usize]
+    }
+
+    pub fn wife3(offset: f32, timing_scale: f32) -> f32 {
+        let offset = offset.abs();
+        let ridiculous = 5. * timing_scale;
+
+        if offset <= ridiculous {
+            return Self::MAX_POINTS;
+        }
+
+        let max_boo_weight = Self::MAX_BOO_WEIGHT * timing_scale;
+        let zero = 65. * timing_scale.powf(Self::JUDGE_POWER);
+        let deviation = 22.7 * timing_scale.powf(Self::JUDGE_POWER);
+
+        if offset <= zero {
+            return Self::MAX_POINTS * erf((zero - offset) / deviation);
+        }
+
+        if offset <= max_boo_weight {
+            return (offset - zero) * Self::MISS_WEIGHT / (max_boo_weight - zero);
+        }
+
+        Self::MISS_WEIGHT
+    }
+
+    // Wife only judges presses; a release only costs points when the hold was dropped, which is
+    // either a missed hold (`i32::MIN`) or a release earlier than the Bad window.
+    pub fn calculate_score_from_hit_difference(
+        &mut self,
+        hit_difference: i32,
+        key_press_type: KeyPressType,
+    ) -> f32 {
+        let points = match key_press_type {
+            KeyPressType::RELEASE => {
+                if hit_difference != i32::MIN
+                    && (hit_difference as f32 / self.mods.rate())
+                        <= Self::MAX_BOO_WEIGHT * self.timing_scale()
+                {
+                    return 0.;
+                }
+
+                self.hold_drops += 1;
+                Self::HOLD_DROP_WEIGHT
+            }
+            _ => {
+                self.judged_notes += 1;
+
+                if hit_difference == i32::MIN {
+                    self.misses += 1;
+                    Self::MISS_WEIGHT
+                } else {
+                    // Etterna windows are in real time, Quaver offsets are in song time.
+                    let offset = hit_difference as f32 / self.mods.rate();
+                    let points = Self::wife3(offset, self.timing_scale());
+
+                    if points == Self::MISS_WEIGHT {
+                        self.misses += 1;
+                    }
+
+                    points
+                }
+            }
+        };
+
+        self.points += points;
+        self.accuracy = self.calculate_accuracy();
+
+        points
+    }
+
+    pub fn calculate_mine_hit(&mut self) {
+        self.mine_hits += 1;
+        self.points += Self::MINE_HIT_WEIGHT;
+        self.accuracy = self.calculate_accuracy();
+    }
+
+    fn calculate_accuracy(&self) -> f32 {
+        if self.judged_notes == 0 {
+            return 0.;
+        }
+
+        self.points / (self.judged_notes as f32 * Self::MAX_POINTS) * 100.
+    }
+
+    // Accuracy over the whole map, counting notes that were never judged as misses.
+    pub fn final_accuracy(&self) -> f32 {
+        if self.total_notes == 0 {
+            return 0.;
+        }
+
+        let unjudged = (self.total_notes - self.judged_notes).max(0);
+        (self.points + unjudged as f32 * Self::MISS_WEIGHT)
+            / (self.total_notes as f32 * Self::MAX_POINTS)
+            * 100.
+    }
+}
+
+// Abramowitz and Stegun 7.1.26, accurate to about 1.5e-7.
+fn erf(x: f32) -> f32 {
+    let sign = x.signum();
+    let x = x.abs() as f64;
+
+    let t = 1. / (1. + 0.3275911 * x);
+    let y = 1.
+        - (((((1.061405429 * t - 1.453152027) * t) + 1.421413741) * t - 0.284496736) * t
+            + 0.254829592)
+            * t
+            * (-x * x).exp();
+
+    sign * y as f32
+}
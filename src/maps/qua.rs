@@ -1,5 +1,5 @@
 use super::structures::*;
-use crate::enums::quaver::{GameMode, ModIdentifier};
+use crate::enums::quaver::{GameMode, LaneLayout, ModIdentifier};
 
 use serde::{Deserialize, Serialize};
 
@@ -93,6 +93,10 @@ impl QuaverMap {
         count
     }
 
+    pub fn lane_layout(&self) -> LaneLayout {
+        LaneLayout::new(self.mode, self.has_scratch_key)
+    }
+
     pub fn get_actions_per_second(&self, rate: Option<f32>) -> f32 {
         let rate = rate.unwrap_or(1.);
         let mut actions: Vec<i32> = Vec::new();
@@ -290,7 +294,7 @@ impl QuaverMap {
             new_hit_objects.push(current_object)
         }
 
-        new_hit_objects.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+        new_hit_objects.sort_by_key(|a| a.start_time);
 
         self.hit_objects = new_hit_objects;
     }
@@ -0,0 +1,476 @@
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
+
+use super::{OsuReplay, QuaverReplay};
+use crate::enums::quaver::ModIdentifier;
+use crate::maps::structures::HitObjectInfo;
+use crate::maps::QuaverMap;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AntiCheatDetector {
+    LowHitErrorVariance,
+    UniformTapDuration,
+    AutoplayMatch,
+    Timewarp,
+    ImpossibleInput,
+}
+
+#[derive(Debug, Clone)]
+pub struct AntiCheatFlag {
+    pub detector: AntiCheatDetector,
+    pub confidence: f32,
+    pub evidence: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AntiCheatReport {
+    pub flags: Vec<AntiCheatFlag>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct KeyInput {
+    lane: i32,
+    press: i32,
+    release: i32,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct MatchedHit {
+    hit_object: HitObjectInfo,
+    input: KeyInput,
+}
+
+impl AntiCheatReport {
+    const MIN_SAMPLE_SIZE: usize = 50;
+    const MATCH_WINDOW_MS: f32 = 164.;
+
+    pub fn from_quaver_replay(replay: &QuaverReplay, map: &QuaverMap) -> Self {
+        // `ModIdentifier::None` is stored as -1, which sets every bit.
+        let rate = if replay.mods == ModIdentifier::None {
+            1.
+        } else {
+            replay.mods.rate()
+        };
+
+        let key_count = map.get_key_count(None);
+        let inputs = Self::key_inputs(replay);
+        let matched = Self::match_inputs(map, &inputs, rate);
+
+        let flags = [
+            Self::detect_low_hit_error_variance(&matched, rate),
+            Self::detect_uniform_tap_duration(&matched, rate),
+            Self::detect_autoplay_match(&matched),
+            Self::detect_timewarp(map, &inputs, rate),
+            Self::detect_impossible_input(replay, map, &inputs, key_count),
+        ];
+
+        Self {
+            flags: flags.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn from_osu_replay(replay: &OsuReplay, map: &QuaverMap) -> Result<Self> {
+        let conversion = QuaverReplay::from_osu_replay(replay, map.get_key_count(None))?;
+        Ok(Self::from_quaver_replay(&conversion.replay, map))
+    }
+
+    pub fn is_flagged(&self, threshold: f32) -> bool {
+        self.flags.iter().any(|f| f.confidence >= threshold)
+    }
+
+    pub fn confidence(&self, detector: AntiCheatDetector) -> f32 {
+        self.flags
+            .iter()
+            .find(|f| f.detector == detector)
+            .map(|f| f.confidence)
+            .unwrap_or_default()
+    }
+
+    fn key_inputs(replay: &QuaverReplay) -> Vec<KeyInput> {
+        let mut inputs: Vec<KeyInput> = Vec::new();
+        let mut pressed_since: HashMap<i32, i32> = HashMap::new();
+
+        for frame in replay.frames.iter() {
+            let lanes = frame.keys.as_lanes();
+
+            for &lane in lanes.iter() {
+                pressed_since.entry(lane).or_insert(frame.time);
+            }
+
+            let released: Vec<i32> = pressed_since
+                .keys()
+                .filter(|lane| !lanes.contains(lane))
+                .copied()
+                .collect();
+
+            for lane in released {
+                let press = pressed_since.remove(&lane).unwrap();
+                inputs.push(KeyInput {
+                    lane,
+                    press,
+                    release: frame.time,
+                });
+            }
+        }
+
+        inputs.sort_by_key(|x| (x.press, x.lane));
+        inputs
+    }
+
+    fn match_inputs(map: &QuaverMap, inputs: &[KeyInput], rate: f32) -> Vec<MatchedHit> {
+        let (hit_objects, inputs_by_lane) = Self::matching_order(map, inputs);
+        Self::match_scaled_inputs(&hit_objects, &inputs_by_lane, rate, 1.)
+    }
+
+    fn matching_order(
+        map: &QuaverMap,
+        inputs: &[KeyInput],
+    ) -> (Vec<HitObjectInfo>, BTreeMap<i32, Vec<KeyInput>>) {
+        let mut inputs_by_lane: BTreeMap<i32, Vec<KeyInput>> = BTreeMap::new();
+        for &input in inputs.iter() {
+            inputs_by_lane.entry(input.lane).or_default().push(input);
+        }
+
+        let mut hit_objects = map.hit_objects.clone();
+        hit_objects.sort_by_key(|x| x.start_time);
+
+        (hit_objects, inputs_by_lane)
+    }
+
+    // Matches presses as if they were recorded at `press / scale`, the matched inputs keep their
+    // recorded times.
+    fn match_scaled_inputs(
+        hit_objects: &[HitObjectInfo],
+        inputs_by_lane: &BTreeMap<i32, Vec<KeyInput>>,
+        rate: f32,
+        scale: f64,
+    ) -> Vec<MatchedHit> {
+        let window = (Self::MATCH_WINDOW_MS * rate) as f64;
+        let press = |input: &KeyInput| input.press as f64 / scale;
+
+        let mut next_input: HashMap<i32, usize> = HashMap::new();
+        let mut matched: Vec<MatchedHit> = Vec::new();
+
+        for &hit_object in hit_objects {
+            let lane_inputs = match inputs_by_lane.get(&hit_object.lane) {
+                Some(lane_inputs) => lane_inputs,
+                None => continue,
+            };
+
+            let start_time = hit_object.start_time as f64;
+            let index = next_input.entry(hit_object.lane).or_default();
+
+            while *index < lane_inputs.len() && press(&lane_inputs[*index]) < start_time - window {
+                *index += 1;
+            }
+
+            if let Some(&input) = lane_inputs.get(*index) {
+                if press(&input) <= start_time + window {
+                    matched.push(MatchedHit { hit_object, input });
+                    *index += 1;
+                }
+            }
+        }
+
+        matched
+    }
+
+    fn mean_and_deviation(values: &[f32]) -> (f32, f32) {
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32;
+
+        (mean, variance.sqrt())
+    }
+
+    fn detect_low_hit_error_variance(matched: &[MatchedHit], rate: f32) -> Option<AntiCheatFlag> {
+        const SUSPICIOUS_DEVIATION_MS: f32 = 8.;
+        const CERTAIN_DEVIATION_MS: f32 = 2.;
+
+        if matched.len() < Self::MIN_SAMPLE_SIZE {
+            return None;
+        }
+
+        // Offsets are stored in song time, convert them back to what the player experienced.
+        let offsets: Vec<f32> = matched
+            .iter()
+            .map(|m| (m.input.press - m.hit_object.start_time) as f32 / rate)
+            .collect();
+
+        let (mean, deviation) = Self::mean_and_deviation(&offsets);
+
+        let confidence = ((SUSPICIOUS_DEVIATION_MS - deviation)
+            / (SUSPICIOUS_DEVIATION_MS - CERTAIN_DEVIATION_MS))
+            .clamp(0., 1.);
+
+        Some(AntiCheatFlag {
+            detector: AntiCheatDetector::LowHitErrorVariance,
+            confidence,
+            evidence: vec![
+                format!("{} matched presses", offsets.len()),
+                format!("mean offset {:.2} ms", mean),
+                format!("standard deviation {:.2} ms", deviation),
+                format!("unstable rate {:.2}", deviation * 10.),
+            ],
+        })
+    }
+
+    fn detect_uniform_tap_duration(matched: &[MatchedHit], rate: f32) -> Option<AntiCheatFlag> {
+        const SUSPICIOUS_MODE_SHARE: f32 = 0.3;
+        const CERTAIN_MODE_SHARE: f32 = 0.8;
+
+        let durations: Vec<i32> = matched
+            .iter()
+            .filter(|m| !m.hit_object.is_long_note())
+            .map(|m| m.input.release - m.input.press)
+            .collect();
+
+        if durations.len() < Self::MIN_SAMPLE_SIZE {
+            return None;
+        }
+
+        let mut counts: HashMap<i32, usize> = HashMap::new();
+        for &duration in durations.iter() {
+            *counts.entry(duration).or_default() += 1;
+        }
+
+        let (&mode, &mode_count) = counts
+            .iter()
+            .max_by_key(|(duration, count)| (**count, -**duration))
+            .unwrap();
+        let mode_share = mode_count as f32 / durations.len() as f32;
+
+        let as_f32: Vec<f32> = durations.iter().map(|&d| d as f32 / rate).collect();
+        let (mean, deviation) = Self::mean_and_deviation(&as_f32);
+
+        let confidence = ((mode_share - SUSPICIOUS_MODE_SHARE)
+            / (CERTAIN_MODE_SHARE - SUSPICIOUS_MODE_SHARE))
+            .clamp(0., 1.);
+
+        Some(AntiCheatFlag {
+            detector: AntiCheatDetector::UniformTapDuration,
+            confidence,
+            evidence: vec![
+                format!("{} taps", durations.len()),
+                format!(
+                    "{:.1}% of taps held for exactly {} ms",
+                    mode_share * 100.,
+                    mode
+                ),
+                format!("mean hold {:.2} ms, deviation {:.2} ms", mean, deviation),
+            ],
+        })
+    }
+
+    // Compares against the frames `QuaverReplay::generate_perfect_replay_keys` would produce.
+    fn detect_autoplay_match(matched: &[MatchedHit]) -> Option<AntiCheatFlag> {
+        const SUSPICIOUS_SHARE: f32 = 0.2;
+        const CERTAIN_SHARE: f32 = 0.8;
+
+        if matched.len() < Self::MIN_SAMPLE_SIZE {
+            return None;
+        }
+
+        let exact_presses = matched
+            .iter()
+            .filter(|m| m.input.press == m.hit_object.start_time)
+            .count();
+
+        let exact_releases = matched
+            .iter()
+            .filter(|m| m.input.release == QuaverReplay::autoplay_release_time(&m.hit_object))
+            .count();
+
+        let press_share = exact_presses as f32 / matched.len() as f32;
+        let release_share = exact_releases as f32 / matched.len() as f32;
+
+        let confidence = ((press_share.max(release_share) - SUSPICIOUS_SHARE)
+            / (CERTAIN_SHARE - SUSPICIOUS_SHARE))
+            .clamp(0., 1.);
+
+        Some(AntiCheatFlag {
+            detector: AntiCheatDetector::AutoplayMatch,
+            confidence,
+            evidence: vec![
+                format!(
+                    "{} of {} presses land exactly on the object",
+                    exact_presses,
+                    matched.len()
+                ),
+                format!(
+                    "{} of {} releases match autoplay timing",
+                    exact_releases,
+                    matched.len()
+                ),
+            ],
+        })
+    }
+
+    // A warped clock drifts out of the match window after a few seconds, so presses are matched
+    // after dividing their times by the factor that lines the most of them up with the map. The
+    // factor is searched on a coarse grid first and then around the best candidate.
+    fn match_rescaled_inputs(map: &QuaverMap, inputs: &[KeyInput], rate: f32) -> Vec<MatchedHit> {
+        const MIN_SCALE: f64 = 0.75;
+        const MAX_SCALE: f64 = 1.35;
+        const FINE_STEPS: i32 = 10;
+
+        let (hit_objects, inputs_by_lane) = Self::matching_order(map, inputs);
+
+        // Between two coarse candidates the last note moves by at most the match window.
+        let length = hit_objects.last().map_or(0, |x| x.start_time).max(1) as f64;
+        let coarse_step = (Self::MATCH_WINDOW_MS * rate) as f64 / length;
+        let fine_step = coarse_step / FINE_STEPS as f64;
+
+        let try_scale = |scale: f64| {
+            let matched = Self::match_scaled_inputs(&hit_objects, &inputs_by_lane, rate, scale);
+            let squared_offsets: f64 = matched
+                .iter()
+                .map(|m| (m.input.press as f64 / scale - m.hit_object.start_time as f64).powi(2))
+                .sum();
+
+            (scale, matched, squared_offsets)
+        };
+
+        // Most matches first, then the smallest offsets.
+        let best = |a: (f64, Vec<MatchedHit>, f64), b: (f64, Vec<MatchedHit>, f64)| {
+            if b.1.len() > a.1.len() || (b.1.len() == a.1.len() && b.2 < a.2) {
+                b
+            } else {
+                a
+            }
+        };
+
+        let steps = ((MAX_SCALE - MIN_SCALE) / coarse_step).ceil() as i32;
+        let coarse = (0..=steps)
+            .map(|i| try_scale(MIN_SCALE + i as f64 * coarse_step))
+            .fold(try_scale(1.), best);
+
+        let coarse_scale = coarse.0;
+        let (_, matched, _) = (-FINE_STEPS..=FINE_STEPS)
+            .map(|i| try_scale(coarse_scale + i as f64 * fine_step))
+            .fold(coarse, best);
+
+        matched
+    }
+
+    // A replay recorded on a clock running at another speed than the claimed rate shows offsets
+    // that grow linearly over the course of the map.
+    fn detect_timewarp(map: &QuaverMap, inputs: &[KeyInput], rate: f32) -> Option<AntiCheatFlag> {
+        const SUSPICIOUS_DRIFT: f32 = 0.0005;
+        const CERTAIN_DRIFT: f32 = 0.0015;
+
+        if inputs.len() < Self::MIN_SAMPLE_SIZE {
+            return None;
+        }
+
+        let matched = Self::match_rescaled_inputs(map, inputs, rate);
+
+        if matched.len() < Self::MIN_SAMPLE_SIZE {
+            return None;
+        }
+
+        // Rescaling only pairs presses with notes, the drift is fitted on the recorded times.
+        let points: Vec<(f64, f64)> = matched
+            .iter()
+            .map(|m| {
+                let start_time = m.hit_object.start_time as f64;
+                (start_time, m.input.press as f64 - start_time)
+            })
+            .collect();
+
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+
+        let covariance: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+        let variance_x: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+        let variance_y: f64 = points.iter().map(|p| (p.1 - mean_y).powi(2)).sum();
+
+        if variance_x == 0. {
+            return None;
+        }
+
+        let slope = covariance / variance_x;
+        let r_squared = if variance_y == 0. {
+            0.
+        } else {
+            covariance.powi(2) / (variance_x * variance_y)
+        };
+
+        let drift = slope.abs() as f32;
+        let confidence = ((drift - SUSPICIOUS_DRIFT) / (CERTAIN_DRIFT - SUSPICIOUS_DRIFT))
+            .clamp(0., 1.)
+            * r_squared as f32;
+
+        Some(AntiCheatFlag {
+            detector: AntiCheatDetector::Timewarp,
+            confidence,
+            evidence: vec![
+                format!("{} presses matched after rescaling", matched.len()),
+                format!("offset drift {:.4} ms per ms of song time", slope),
+                format!("fit r² {:.3}", r_squared),
+                format!(
+                    "claimed rate {:.2}x, effective rate {:.3}x",
+                    rate,
+                    rate as f64 / (1. + slope)
+                ),
+            ],
+        })
+    }
+
+    fn detect_impossible_input(
+        replay: &QuaverReplay,
+        map: &QuaverMap,
+        inputs: &[KeyInput],
+        key_count: i32,
+    ) -> Option<AntiCheatFlag> {
+        let mut evidence: Vec<String> = Vec::new();
+
+        let out_of_range = inputs
+            .iter()
+            .filter(|i| i.lane < 1 || i.lane > key_count)
+            .count();
+        if out_of_range > 0 {
+            evidence.push(format!(
+                "{} presses on lanes outside of {}K",
+                out_of_range, key_count
+            ));
+        }
+
+        let mut chord_sizes: HashMap<i32, usize> = HashMap::new();
+        for hit_object in map.hit_objects.iter() {
+            *chord_sizes.entry(hit_object.start_time).or_default() += 1;
+        }
+        let largest_chord = chord_sizes.values().copied().max().unwrap_or(1);
+
+        // More keys going down on the exact same millisecond than the map ever asks for.
+        let mut frame_presses = 0;
+        let mut previous = None;
+        for frame in replay.frames.iter() {
+            if let Some(previous) = previous {
+                let newly_pressed = (frame.keys - previous).as_lanes().len();
+                if newly_pressed > largest_chord.max(2) {
+                    frame_presses += 1;
+                }
+            }
+            previous = Some(frame.keys);
+        }
+        if frame_presses > 0 {
+            evidence.push(format!(
+                "{} frames press more keys at once than the largest chord ({})",
+                frame_presses, largest_chord
+            ));
+        }
+
+        if evidence.is_empty() {
+            return None;
+        }
+
+        let occurrences = out_of_range + frame_presses;
+
+        Some(AntiCheatFlag {
+            detector: AntiCheatDetector::ImpossibleInput,
+            confidence: (occurrences as f32 / 5.).clamp(0., 1.),
+            evidence,
+        })
+    }
+}
@@ -0,0 +1,249 @@
+use anyhow::{anyhow, Result};
+
+use crate::enums::quaver::LaneLayout;
+use crate::enums::{osu, quaver};
+use crate::maps::processors::{Judgement, ScoreProcessor};
+
+use super::osu::{KeyPressState, ReplayEvent};
+use super::quaver::{ReplayFrame, ReplayKeyPressState};
+use super::{OsuReplay, QuaverReplay};
+
+// Offset between .NET ticks (0001-01-01) and the unix epoch.
+const UNIX_EPOCH_TICKS: i64 = 621_355_968_000_000_000;
+const TICKS_PER_MILLISECOND: i64 = 10_000;
+
+pub struct ReplayConversion<R, M> {
+    pub replay: R,
+    pub unmapped_mods: M,
+}
+
+impl QuaverReplay {
+    // Fails for key counts Quaver has no game mode for.
+    pub fn from_osu_replay(
+        replay: &OsuReplay,
+        key_count: i32,
+    ) -> Result<ReplayConversion<QuaverReplay, osu::ModIdentifier>> {
+        let layout = LaneLayout::from_key_count(key_count)
+            .ok_or_else(|| anyhow!("Quaver has no game mode for {} keys", key_count))?;
+        let (mods, unmapped_mods) = osu_mods_to_quaver(replay.mods, key_count);
+
+        let mut self_ = QuaverReplay {
+            map_md5: replay.map_md5.clone(),
+            md5: replay.replay_md5.clone(),
+            player_name: replay.username.clone(),
+            time_played: (replay.time_stamp as i64 - UNIX_EPOCH_TICKS) / TICKS_PER_MILLISECOND,
+            mode: layout.mode() as i32,
+            mods,
+            // osu! and Quaver score on different scales, so the score is carried over as-is.
+            score: replay.score as i32,
+            max_combo: replay.max_combo as i32,
+            count_marv: replay.count_geki as i32,
+            count_perf: replay.count_300 as i32,
+            count_great: replay.count_katu as i32,
+            count_good: replay.count_100 as i32,
+            count_okay: replay.count_50 as i32,
+            count_miss: replay.count_miss as i32,
+            ..Default::default()
+        };
+
+        self_.accuracy = self_.calculate_accuracy_from_counts();
+
+        self_.frames.push(ReplayFrame {
+            time: -10000,
+            keys: ReplayKeyPressState::empty(),
+        });
+
+        for frame in replay.frames() {
+            let mut keys = ReplayKeyPressState::empty();
+
+            for column in frame.keys.as_lanes(key_count) {
+                keys |= layout
+                    .osu_column_to_lane(column)
+                    .and_then(ReplayKeyPressState::from_lane)
+                    .unwrap_or_else(ReplayKeyPressState::empty);
+            }
+
+            self_.frames.push(ReplayFrame {
+                time: frame.time as i32,
+                keys,
+            });
+        }
+
+        Ok(ReplayConversion {
+            replay: self_,
+            unmapped_mods,
+        })
+    }
+
+    fn calculate_accuracy_from_counts(&self) -> f32 {
+        let weighting = ScoreProcessor::judgement_accuracy_weighting();
+        let counts = [
+            (Judgement::Marv, self.count_marv),
+            (Judgement::Perf, self.count_perf),
+            (Judgement::Great, self.count_great),
+            (Judgement::Good, self.count_good),
+            (Judgement::Okay, self.count_okay),
+            (Judgement::Miss, self.count_miss),
+        ];
+
+        let total: i32 = counts.iter().map(|(_, count)| count).sum();
+
+        if total == 0 {
+            return 0.;
+        }
+
+        let acc: f32 = counts
+            .iter()
+            .map(|(judgement, count)| *count as f32 * weighting[judgement])
+            .sum();
+
+        0f32.max(acc / (total as f32 * weighting[&Judgement::Marv])) * weighting[&Judgement::Marv]
+    }
+}
+
+impl OsuReplay {
+    pub fn from_quaver_replay(
+        replay: &QuaverReplay,
+        key_count: i32,
+    ) -> ReplayConversion<OsuReplay, quaver::ModIdentifier> {
+        let (mods, unmapped_mods) = quaver_mods_to_osu(replay.mods);
+        let layout = LaneLayout::from_key_count(key_count);
+
+        let mut self_ = OsuReplay {
+            mode: 3,
+            map_md5: replay.map_md5.clone(),
+            username: replay.player_name.clone(),
+            replay_md5: replay.md5.clone(),
+            count_geki: replay.count_marv.clamp(0, u16::MAX as i32) as u16,
+            count_300: replay.count_perf.clamp(0, u16::MAX as i32) as u16,
+            count_katu: replay.count_great.clamp(0, u16::MAX as i32) as u16,
+            count_100: replay.count_good.clamp(0, u16::MAX as i32) as u16,
+            count_50: replay.count_okay.clamp(0, u16::MAX as i32) as u16,
+            count_miss: replay.count_miss.clamp(0, u16::MAX as i32) as u16,
+            score: replay.score.max(0) as u32,
+            max_combo: replay.max_combo.clamp(0, u16::MAX as i32) as u16,
+            perfect: (replay.count_miss == 0) as u8,
+            mods,
+            time_stamp: (replay.time_played * TICKS_PER_MILLISECOND + UNIX_EPOCH_TICKS) as u64,
+            ..Default::default()
+        };
+
+        let mut previous_time = 0;
+
+        for frame in replay.frames.iter() {
+            let mut keys = KeyPressState::empty();
+
+            for lane in frame.keys.as_lanes() {
+                let column = layout.map_or(Some(lane), |l| l.lane_to_osu_column(lane));
+                keys |= column
+                    .map(|c| KeyPressState::from_bits_truncate(1 << (c - 1)))
+                    .unwrap_or_else(KeyPressState::empty);
+            }
+
+            self_.replay_data.push(ReplayEvent {
+                time_delta: (frame.time - previous_time) as i64,
+                keys,
+            });
+
+            previous_time = frame.time;
+        }
+
+        ReplayConversion {
+            replay: self_,
+            unmapped_mods,
+        }
+    }
+}
+
+fn osu_mods_to_quaver(
+    mods: osu::ModIdentifier,
+    key_count: i32,
+) -> (quaver::ModIdentifier, osu::ModIdentifier) {
+    let mut converted = quaver::ModIdentifier::empty();
+    let mut mapped = osu::ModIdentifier::empty();
+
+    for (from, to) in [
+        (osu::ModIdentifier::NoFail, quaver::ModIdentifier::NoFail),
+        (osu::ModIdentifier::Mirror, quaver::ModIdentifier::Mirror),
+        (
+            osu::ModIdentifier::Autoplay,
+            quaver::ModIdentifier::Autoplay,
+        ),
+        (osu::ModIdentifier::Random, quaver::ModIdentifier::Randomize),
+        (
+            osu::ModIdentifier::SuddenDeath,
+            quaver::ModIdentifier::NoMiss,
+        ),
+        (
+            osu::ModIdentifier::DoubleTime,
+            quaver::ModIdentifier::Speed15X,
+        ),
+        (
+            osu::ModIdentifier::Nightcore,
+            quaver::ModIdentifier::Speed15X,
+        ),
+        (
+            osu::ModIdentifier::HalfTime,
+            quaver::ModIdentifier::Speed075X,
+        ),
+    ] {
+        if mods.contains(from) {
+            converted |= to;
+            mapped |= from;
+        }
+    }
+
+    // Key mods are folded into the Quaver game mode when it can represent the key count.
+    let key_mods = osu::ModIdentifier::KeyMod
+        | osu::ModIdentifier::Key1
+        | osu::ModIdentifier::Key2
+        | osu::ModIdentifier::Key3
+        | osu::ModIdentifier::Key9;
+
+    if LaneLayout::from_key_count(key_count).is_some()
+        && mods.key_count().unwrap_or(key_count) == key_count
+    {
+        mapped |= mods & key_mods;
+    }
+
+    (converted, mods - mapped)
+}
+
+fn quaver_mods_to_osu(mods: quaver::ModIdentifier) -> (osu::ModIdentifier, quaver::ModIdentifier) {
+    let mut converted = osu::ModIdentifier::empty();
+    let mut mapped = quaver::ModIdentifier::empty();
+
+    // `ModIdentifier::None` is stored as -1, which sets every bit.
+    if mods == quaver::ModIdentifier::None {
+        return (converted, mapped);
+    }
+
+    for (from, to) in [
+        (quaver::ModIdentifier::NoFail, osu::ModIdentifier::NoFail),
+        (quaver::ModIdentifier::Mirror, osu::ModIdentifier::Mirror),
+        (
+            quaver::ModIdentifier::Autoplay,
+            osu::ModIdentifier::Autoplay,
+        ),
+        (quaver::ModIdentifier::Randomize, osu::ModIdentifier::Random),
+        (
+            quaver::ModIdentifier::NoMiss,
+            osu::ModIdentifier::SuddenDeath,
+        ),
+        (
+            quaver::ModIdentifier::Speed15X,
+            osu::ModIdentifier::DoubleTime,
+        ),
+        (
+            quaver::ModIdentifier::Speed075X,
+            osu::ModIdentifier::HalfTime,
+        ),
+    ] {
+        if mods.contains(from) {
+            converted |= to;
+            mapped |= from;
+        }
+    }
+
+    (converted, mods - mapped)
+}
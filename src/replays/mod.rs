@@ -1,5 +1,9 @@
-mod osu;
-mod quaver;
+mod anti_cheat;
+mod conversion;
+pub mod osu;
+pub mod quaver;
 
+pub use anti_cheat::*;
+pub use conversion::*;
 pub use osu::OsuReplay;
 pub use quaver::QuaverReplay;
@@ -21,3 +21,15 @@ bitflags! {
         const K18 = 1 << 17;
     }
 }
+
+impl KeyPressState {
+    pub fn is_lane_pressed(&self, lane: i32) -> bool {
+        (1..=18).contains(&lane) && self.bits() & (1 << (lane - 1)) != 0
+    }
+
+    pub fn as_lanes(&self, key_count: i32) -> Vec<i32> {
+        (1..=key_count)
+            .filter(|&lane| self.is_lane_pressed(lane))
+            .collect()
+    }
+}
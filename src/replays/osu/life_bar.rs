@@ -0,0 +1,114 @@
+use crate::maps::processors::{HealthSample, ScoreProcessor};
+use anyhow::{anyhow, Result};
+
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+pub struct LifeBarSample {
+    pub time: i32,
+    pub health: f32,
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct LifeBarGraph {
+    pub samples: Vec<LifeBarSample>,
+}
+
+impl LifeBarGraph {
+    pub fn from_osu_string(life_bar: &str) -> Result<Self> {
+        let mut self_ = Self::default();
+
+        for sample in life_bar.split(',').filter(|s| !s.trim().is_empty()) {
+            let (time, health) = sample
+                .split_once('|')
+                .ok_or_else(|| anyhow!("Malformed life bar sample: {}", sample))?;
+
+            self_.samples.push(LifeBarSample {
+                time: time.trim().parse()?,
+                health: health.trim().parse()?,
+            });
+        }
+
+        self_.samples.sort_by_key(|s| s.time);
+
+        Ok(self_)
+    }
+
+    // Quaver health ranges 0..100 while osu! stores it as 0..1.
+    pub fn from_health_timeline(timeline: &[HealthSample]) -> Self {
+        Self {
+            samples: timeline
+                .iter()
+                .map(|sample| LifeBarSample {
+                    time: sample.time,
+                    health: sample.health / 100.,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn from_score_processor(processor: &ScoreProcessor) -> Self {
+        Self::from_health_timeline(&processor.health_timeline)
+    }
+
+    pub fn to_osu_string(&self) -> String {
+        self.samples
+            .iter()
+            .map(|s| format!("{}|{},", s.time, s.health))
+            .collect()
+    }
+
+    pub fn lowest_health(&self) -> Option<LifeBarSample> {
+        self.samples
+            .iter()
+            .copied()
+            .reduce(|lowest, s| if s.health < lowest.health { s } else { lowest })
+    }
+
+    pub fn time_of_lowest_health(&self) -> Option<i32> {
+        self.lowest_health().map(|s| s.time)
+    }
+
+    pub fn reached_zero(&self) -> bool {
+        self.samples.iter().any(|s| s.health <= 0.)
+    }
+
+    pub fn health_at(&self, time: i32) -> Option<f32> {
+        let next = self.samples.iter().position(|s| s.time >= time);
+
+        match next {
+            None => self.samples.last().map(|s| s.health),
+            Some(0) => self.samples.first().map(|s| s.health),
+            Some(i) => {
+                let previous = self.samples[i - 1];
+                let next = self.samples[i];
+                let progress = (time - previous.time) as f32 / (next.time - previous.time) as f32;
+
+                Some(previous.health + (next.health - previous.health) * progress)
+            }
+        }
+    }
+
+    pub fn resample(&self, resolution: usize) -> Self {
+        let (first, last) = match (self.samples.first(), self.samples.last()) {
+            (Some(first), Some(last)) if resolution > 1 => (first.time, last.time),
+            _ => {
+                return Self {
+                    samples: self.samples.iter().take(resolution).copied().collect(),
+                }
+            }
+        };
+
+        let step = (last - first) as f64 / (resolution - 1) as f64;
+
+        Self {
+            samples: (0..resolution)
+                .map(|i| {
+                    let time = first + (step * i as f64).round() as i32;
+                    LifeBarSample {
+                        time,
+                        health: self.health_at(time).unwrap_or_default(),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
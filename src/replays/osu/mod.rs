@@ -1,5 +1,9 @@
 pub mod key_press_states;
+mod life_bar;
 mod osu_replay;
+mod replay_lane_event;
 
 pub use key_press_states::*;
+pub use life_bar::*;
 pub use osu_replay::*;
+pub use replay_lane_event::*;
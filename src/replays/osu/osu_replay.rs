@@ -1,10 +1,12 @@
 use crate::enums::osu::ModIdentifier;
-use crate::helpers::BinaryHelper;
+use crate::helpers::{write_osu_string, BinaryHelper};
+use crate::maps::parsers::OsuBeatmap;
 use anyhow::{anyhow, Result};
-use lzma_rs::lzma_decompress;
+use byteorder::{LittleEndian, WriteBytesExt};
+use lzma_rs::{lzma_compress, lzma_decompress};
 use std::fs;
 
-use super::KeyPressState;
+use super::{KeyPressState, LifeBarGraph, ReplayLaneEvent, ReplayLaneEventType};
 
 #[derive(Default)]
 pub struct OsuReplay {
@@ -88,7 +90,7 @@ impl OsuReplay {
 
             let time_delta: i64 = event_split[0].parse().unwrap();
 
-            if time_delta == -12345 && i == event.len() - 1 {
+            if time_delta == -12345 && i == events.len() - 1 {
                 self.rng_seed = event_split[3].parse().unwrap();
                 continue;
             }
@@ -105,6 +107,109 @@ impl OsuReplay {
 
         Ok(())
     }
+
+    // The RNG seed is written as the trailing `-12345` frame, like osu! does.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut data: Vec<u8> = Vec::new();
+
+        data.write_u8(self.mode)?;
+        data.write_u32::<LittleEndian>(self.version)?;
+        write_osu_string(&mut data, &self.map_md5);
+        write_osu_string(&mut data, &self.username);
+        write_osu_string(&mut data, &self.replay_md5);
+
+        for count in [
+            self.count_300,
+            self.count_100,
+            self.count_50,
+            self.count_geki,
+            self.count_katu,
+            self.count_miss,
+        ] {
+            data.write_u16::<LittleEndian>(count)?;
+        }
+
+        data.write_u32::<LittleEndian>(self.score)?;
+        data.write_u16::<LittleEndian>(self.max_combo)?;
+        data.write_u8(self.perfect)?;
+        data.write_u32::<LittleEndian>(self.mods.bits())?;
+        write_osu_string(&mut data, &self.life_bar);
+        data.write_u64::<LittleEndian>(self.time_stamp)?;
+
+        let mut events: String = self
+            .replay_data
+            .iter()
+            .map(|e| format!("{}|{}|0|0,", e.time_delta, e.keys.bits()))
+            .collect();
+        events.push_str(&format!("-12345|0|0|{},", self.rng_seed));
+
+        let mut compressed: Vec<u8> = Vec::new();
+        lzma_compress(&mut events.as_bytes(), &mut compressed)?;
+        data.write_u32::<LittleEndian>(compressed.len() as u32)?;
+        data.extend_from_slice(&compressed);
+
+        data.write_u64::<LittleEndian>(self.replay_id)?;
+
+        Ok(data)
+    }
+
+    pub fn to_file(&self, path: &str) -> Result<()> {
+        Ok(fs::write(path, self.to_bytes()?)?)
+    }
+
+    pub fn life_bar_graph(&self) -> Result<LifeBarGraph> {
+        LifeBarGraph::from_osu_string(&self.life_bar)
+    }
+
+    pub fn set_life_bar_graph(&mut self, graph: &LifeBarGraph) {
+        self.life_bar = graph.to_osu_string();
+    }
+
+    pub fn key_count(&self, beatmap: Option<&OsuBeatmap>) -> Option<i32> {
+        self.mods
+            .key_count()
+            .or_else(|| beatmap.map(|b| b.key_count).filter(|&k| k > 0))
+    }
+
+    pub fn frames(&self) -> impl Iterator<Item = AbsoluteReplayEvent> + '_ {
+        self.replay_data.iter().scan(0i64, |time, event| {
+            *time += event.time_delta;
+            Some(AbsoluteReplayEvent {
+                time: *time,
+                keys: event.keys,
+            })
+        })
+    }
+
+    pub fn lane_events(&self, key_count: i32) -> Vec<ReplayLaneEvent> {
+        let mut lane_events: Vec<ReplayLaneEvent> = Vec::new();
+        let mut previous = KeyPressState::empty();
+
+        for frame in self.frames() {
+            for lane in 1..=key_count {
+                let was_pressed = previous.is_lane_pressed(lane);
+                let is_pressed = frame.keys.is_lane_pressed(lane);
+
+                if was_pressed == is_pressed {
+                    continue;
+                }
+
+                lane_events.push(ReplayLaneEvent {
+                    time: frame.time,
+                    lane,
+                    type_: if is_pressed {
+                        ReplayLaneEventType::Press
+                    } else {
+                        ReplayLaneEventType::Release
+                    },
+                });
+            }
+
+            previous = frame.keys;
+        }
+
+        lane_events
+    }
 }
 
 #[derive(Default, Debug)]
@@ -112,3 +217,9 @@ pub struct ReplayEvent {
     pub time_delta: i64,
     pub keys: KeyPressState,
 }
+
+#[derive(Default, Debug, Copy, Clone)]
+pub struct AbsoluteReplayEvent {
+    pub time: i64,
+    pub keys: KeyPressState,
+}
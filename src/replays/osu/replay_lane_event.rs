@@ -0,0 +1,12 @@
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ReplayLaneEvent {
+    pub time: i64,
+    pub lane: i32,
+    pub type_: ReplayLaneEventType,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReplayLaneEventType {
+    Press,
+    Release,
+}
@@ -0,0 +1,207 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::{BTreeMap, HashMap};
+
+use super::{QuaverReplay, ReplayFrame, ReplayKeyPressState};
+use crate::maps::QuaverMap;
+
+// Offsets are expressed as input time minus object time, so a positive value is late.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OffsetDistribution {
+    Gaussian {
+        mean: f32,
+        standard_deviation: f32,
+    },
+    SkewNormal {
+        location: f32,
+        scale: f32,
+        shape: f32,
+    },
+}
+
+impl OffsetDistribution {
+    fn sample(&self, rng: &mut ChaCha8Rng) -> f32 {
+        match *self {
+            OffsetDistribution::Gaussian {
+                mean,
+                standard_deviation,
+            } => mean + standard_deviation * standard_normal(rng),
+            OffsetDistribution::SkewNormal {
+                location,
+                scale,
+                shape,
+            } => {
+                let delta = shape / (1. + shape * shape).sqrt();
+                let u0 = standard_normal(rng);
+                let u1 = standard_normal(rng);
+                let z = delta * u0.abs() + (1. - delta * delta).sqrt() * u1;
+
+                location + scale * z
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HumanizedReplaySettings {
+    pub seed: u64,
+    pub press_offset: OffsetDistribution,
+    pub release_offset: OffsetDistribution,
+    pub tap_duration: OffsetDistribution,
+    // Each lane is played by its own finger, so the bias is keyed by lane.
+    pub finger_bias: HashMap<i32, f32>,
+    pub chord_split_ms: f32,
+    pub miss_rate: f32,
+    pub ghost_tap_rate: f32,
+}
+
+impl Default for HumanizedReplaySettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            press_offset: OffsetDistribution::Gaussian {
+                mean: 0.,
+                standard_deviation: 15.,
+            },
+            release_offset: OffsetDistribution::Gaussian {
+                mean: 0.,
+                standard_deviation: 25.,
+            },
+            tap_duration: OffsetDistribution::Gaussian {
+                mean: 60.,
+                standard_deviation: 12.,
+            },
+            finger_bias: HashMap::new(),
+            chord_split_ms: 0.,
+            miss_rate: 0.,
+            ghost_tap_rate: 0.,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct KeyInput {
+    lane: i32,
+    press: i32,
+    release: i32,
+}
+
+impl QuaverReplay {
+    pub fn generate_humanized_replay(
+        map: &QuaverMap,
+        settings: &HumanizedReplaySettings,
+    ) -> QuaverReplay {
+        let mut rng = ChaCha8Rng::seed_from_u64(settings.seed);
+        let mut inputs: Vec<KeyInput> = Vec::new();
+
+        let mut hit_objects = map.hit_objects.clone();
+        hit_objects.sort_by_key(|x| x.start_time);
+
+        let key_count = map.get_key_count(None);
+
+        let mut notes_at_time: HashMap<i32, usize> = HashMap::new();
+        for hit_object in hit_objects.iter() {
+            *notes_at_time.entry(hit_object.start_time).or_default() += 1;
+        }
+
+        for (i, hit_object) in hit_objects.iter().enumerate() {
+            if rng.gen::<f32>() < settings.miss_rate {
+                continue;
+            }
+
+            let mut offset = settings.press_offset.sample(&mut rng)
+                + settings
+                    .finger_bias
+                    .get(&hit_object.lane)
+                    .copied()
+                    .unwrap_or_default();
+
+            if notes_at_time[&hit_object.start_time] > 1 && settings.chord_split_ms > 0. {
+                offset += rng.gen_range(0.0..settings.chord_split_ms);
+            }
+
+            let press = hit_object.start_time + offset.round() as i32;
+
+            let release = if hit_object.is_long_note() {
+                hit_object.end_time + settings.release_offset.sample(&mut rng).round() as i32
+            } else {
+                press + settings.tap_duration.sample(&mut rng).round() as i32
+            };
+
+            inputs.push(KeyInput {
+                lane: hit_object.lane,
+                press,
+                release: release.max(press + 1),
+            });
+
+            if rng.gen::<f32>() < settings.ghost_tap_rate {
+                let next_time = hit_objects
+                    .get(i + 1)
+                    .map(|o| o.start_time)
+                    .unwrap_or(hit_object.start_time + 1000);
+                let press =
+                    rng.gen_range(hit_object.start_time..next_time.max(hit_object.start_time + 1));
+                let duration = settings.tap_duration.sample(&mut rng).round() as i32;
+
+                inputs.push(KeyInput {
+                    lane: rng.gen_range(1..=key_count),
+                    press,
+                    release: press + duration.max(1),
+                });
+            }
+        }
+
+        Self::frames_from_inputs(&mut inputs)
+    }
+
+    fn frames_from_inputs(inputs: &mut [KeyInput]) -> QuaverReplay {
+        let mut replay = QuaverReplay::default();
+
+        // A lane can't be pressed again before it was released, so cut overlapping holds short.
+        inputs.sort_by_key(|x| (x.lane, x.press));
+        for i in 1..inputs.len() {
+            if inputs[i].lane == inputs[i - 1].lane && inputs[i - 1].release >= inputs[i].press {
+                inputs[i - 1].release = inputs[i].press - 1;
+            }
+        }
+
+        let mut grouped: BTreeMap<i32, Vec<(ReplayKeyPressState, bool)>> = BTreeMap::new();
+        for input in inputs.iter().filter(|x| x.release > x.press) {
+            let keys = ReplayKeyPressState::from_lane(input.lane).unwrap();
+            grouped.entry(input.press).or_default().push((keys, true));
+            grouped
+                .entry(input.release)
+                .or_default()
+                .push((keys, false));
+        }
+
+        replay.frames.push(ReplayFrame {
+            time: -10000,
+            keys: ReplayKeyPressState::empty(),
+        });
+
+        let mut state = ReplayKeyPressState::empty();
+
+        for (time, changes) in grouped {
+            for (keys, pressed) in changes {
+                if pressed {
+                    state |= keys;
+                } else {
+                    state &= !keys;
+                }
+            }
+
+            replay.frames.push(ReplayFrame { time, keys: state })
+        }
+
+        replay
+    }
+}
+
+// Box-Muller transform, so the generator only depends on a seeded uniform source.
+fn standard_normal(rng: &mut ChaCha8Rng) -> f32 {
+    let u1: f32 = rng.gen_range(f32::EPSILON..1.0);
+    let u2: f32 = rng.gen();
+
+    (-2. * u1.ln()).sqrt() * (2. * std::f32::consts::PI * u2).cos()
+}
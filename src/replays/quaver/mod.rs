@@ -1,8 +1,11 @@
+mod humanized_replay;
 mod quaver_replay;
 mod replay_autoplay_frame;
+mod replay_editing;
 mod replay_frame;
 mod replay_key_press_state;
 
+pub use humanized_replay::*;
 pub use quaver_replay::*;
 pub use replay_autoplay_frame::*;
 pub use replay_frame::*;
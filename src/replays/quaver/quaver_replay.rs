@@ -1,6 +1,12 @@
 use super::{ReplayAutoplayFrame, ReplayAutoplayFrameType, ReplayFrame, ReplayKeyPressState};
-use crate::{enums::quaver::ModIdentifier, helpers::BinaryHelper, maps::QuaverMap};
-use lzma_rs::lzma_decompress;
+use crate::{
+    enums::quaver::ModIdentifier,
+    helpers::{write_string, BinaryHelper},
+    maps::{structures::HitObjectInfo, QuaverMap},
+};
+use anyhow::Result;
+use byteorder::{LittleEndian, WriteBytesExt};
+use lzma_rs::{lzma_compress, lzma_decompress};
 use semver::Version;
 use std::collections::BTreeMap;
 use std::fs;
@@ -25,7 +31,11 @@ pub struct QuaverReplay {
     pub count_okay: i32,
     pub count_miss: i32,
     pub pause_count: i32,
+    // Seed of the Randomize mod, only stored by versioned replays.
+    pub randomize_modifier_seed: i32,
     pub frames: Vec<ReplayFrame>,
+    // Set once the frames were edited and the header counts no longer describe them.
+    pub header_stale: bool,
 }
 
 impl QuaverReplay {
@@ -74,7 +84,7 @@ impl QuaverReplay {
             let _replay_version = Version::parse(self_.replay_version.as_str()).unwrap();
 
             if _replay_version >= Version::parse("0.0.1").unwrap() {
-                br.read_i32().unwrap();
+                self_.randomize_modifier_seed = br.read_i32().unwrap();
             }
         }
 
@@ -105,6 +115,86 @@ impl QuaverReplay {
         self_
     }
 
+    // Writes the replay in the layout `from_file` reads. Replays without a version are written as
+    // "None", the oldest layout.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut data: Vec<u8> = Vec::new();
+
+        let replay_version = match self.replay_version.as_str() {
+            "" => "None",
+            version => version,
+        };
+
+        for string in [
+            replay_version,
+            &self.map_md5,
+            &self.md5,
+            &self.player_name,
+            &self.date,
+        ] {
+            write_string(&mut data, string);
+        }
+
+        data.write_i64::<LittleEndian>(self.time_played)?;
+        data.write_i32::<LittleEndian>(self.mode)?;
+
+        if replay_version == "0.0.1" || replay_version == "None" {
+            let mods = if self.mods == ModIdentifier::None {
+                -1
+            } else {
+                self.mods.bits() as i32
+            };
+            data.write_i32::<LittleEndian>(mods)?;
+        } else {
+            data.write_i64::<LittleEndian>(self.mods.bits())?;
+        }
+
+        data.write_i32::<LittleEndian>(self.score)?;
+        data.write_f32::<LittleEndian>(self.accuracy)?;
+
+        for value in [
+            self.max_combo,
+            self.count_marv,
+            self.count_perf,
+            self.count_great,
+            self.count_good,
+            self.count_okay,
+            self.count_miss,
+            self.pause_count,
+        ] {
+            data.write_i32::<LittleEndian>(value)?;
+        }
+
+        if replay_version != "None" {
+            data.write_i32::<LittleEndian>(self.randomize_modifier_seed)?;
+        }
+
+        let frames: String = self
+            .frames
+            .iter()
+            .map(|f| format!("{}|{},", f.time, f.keys.bits()))
+            .collect();
+        lzma_compress(&mut frames.as_bytes(), &mut data)?;
+
+        Ok(data)
+    }
+
+    pub fn to_file(&self, path: &str) -> Result<()> {
+        Ok(fs::write(path, self.to_bytes()?)?)
+    }
+
+    // Autoplay taps are held for a fixed time and long notes are let go just before their end.
+    pub const AUTOPLAY_TAP_DURATION: i32 = 30;
+    pub const AUTOPLAY_LONG_NOTE_RELEASE_OFFSET: i32 = -1;
+
+    pub fn autoplay_release_time(hit_object: &HitObjectInfo) -> i32 {
+        if hit_object.is_long_note() {
+            hit_object.end_time + Self::AUTOPLAY_LONG_NOTE_RELEASE_OFFSET
+        } else {
+            hit_object.start_time + Self::AUTOPLAY_TAP_DURATION
+        }
+    }
+
     pub fn generate_perfect_replay_keys(map: &QuaverMap) -> QuaverReplay {
         let mut non_combined: Vec<ReplayAutoplayFrame> = Vec::new();
         let mut replay = QuaverReplay::default();
@@ -114,24 +204,15 @@ impl QuaverReplay {
                 hit_object,
                 type_: ReplayAutoplayFrameType::Press,
                 time: hit_object.start_time,
-                keys: Self::key_lane_to_press_state(hit_object.lane).unwrap(),
+                keys: ReplayKeyPressState::from_lane(hit_object.lane).unwrap(),
             });
 
-            if hit_object.end_time > 0 {
-                non_combined.push(ReplayAutoplayFrame {
-                    hit_object,
-                    type_: ReplayAutoplayFrameType::Release,
-                    time: hit_object.end_time - 1,
-                    keys: Self::key_lane_to_press_state(hit_object.lane).unwrap(),
-                })
-            } else {
-                non_combined.push(ReplayAutoplayFrame {
-                    hit_object,
-                    type_: ReplayAutoplayFrameType::Release,
-                    time: hit_object.start_time + 30,
-                    keys: Self::key_lane_to_press_state(hit_object.lane).unwrap(),
-                })
-            }
+            non_combined.push(ReplayAutoplayFrame {
+                hit_object,
+                type_: ReplayAutoplayFrameType::Release,
+                time: Self::autoplay_release_time(&hit_object),
+                keys: ReplayKeyPressState::from_lane(hit_object.lane).unwrap(),
+            });
         }
 
         non_combined.sort_by_key(|x| x.time);
@@ -159,10 +240,6 @@ impl QuaverReplay {
         replay
     }
 
-    fn key_lane_to_press_state(lane: i32) -> Option<ReplayKeyPressState> {
-        ReplayKeyPressState::from_bits(1 << (lane - 1))
-    }
-
     fn group_auto_frames(
         vec: &Vec<ReplayAutoplayFrame>,
     ) -> BTreeMap<i32, Vec<ReplayAutoplayFrame>> {
@@ -0,0 +1,185 @@
+use anyhow::{anyhow, Result};
+
+use super::{QuaverReplay, ReplayFrame, ReplayKeyPressState};
+use crate::enums::quaver::LaneLayout;
+
+impl QuaverReplay {
+    const LEADING_FRAME_TIME: i32 = -10000;
+
+    pub fn trim(&mut self, start: i32, end: i32) {
+        let carried_state = self
+            .frames
+            .iter()
+            .filter(|f| f.time <= start)
+            .max_by_key(|f| f.time)
+            .map(|f| f.keys)
+            .unwrap_or_else(ReplayKeyPressState::empty);
+
+        let mut frames: Vec<ReplayFrame> = vec![ReplayFrame {
+            time: start,
+            keys: carried_state,
+        }];
+
+        frames.extend(
+            self.frames
+                .iter()
+                .filter(|f| f.time > start && f.time < end)
+                .copied(),
+        );
+
+        // Release everything still held when the cut ends.
+        frames.push(ReplayFrame {
+            time: end,
+            keys: ReplayKeyPressState::empty(),
+        });
+
+        self.frames = frames;
+        self.normalize_frames();
+        self.header_stale = true;
+    }
+
+    pub fn shift(&mut self, offset: i32) {
+        for frame in self.frames.iter_mut() {
+            frame.time += offset;
+        }
+
+        self.normalize_frames();
+        self.header_stale = true;
+    }
+
+    // `mapping[i]` is the new 1-based lane for lane `i + 1`. The mapping is checked against every
+    // frame before anything is changed, so the replay is left untouched when it doesn't apply.
+    pub fn remap_lanes(&mut self, mapping: &[i32]) -> Result<()> {
+        if let Some(&lane) = mapping.iter().find(|&&l| !(1..=9).contains(&l)) {
+            return Err(anyhow!(
+                "Lane {} can't be represented in a replay frame",
+                lane
+            ));
+        }
+
+        for (i, lane) in mapping.iter().enumerate() {
+            if let Some(j) = mapping[..i].iter().position(|l| l == lane) {
+                return Err(anyhow!(
+                    "Lanes {} and {} are both mapped to lane {}",
+                    j + 1,
+                    i + 1,
+                    lane
+                ));
+            }
+        }
+
+        let used_lanes = self
+            .frames
+            .iter()
+            .fold(ReplayKeyPressState::empty(), |used, f| used | f.keys);
+
+        if let Some(lane) = used_lanes
+            .as_lanes()
+            .into_iter()
+            .find(|&l| l as usize > mapping.len())
+        {
+            return Err(anyhow!("No mapping for lane {}", lane));
+        }
+
+        let frames = self
+            .frames
+            .iter()
+            .map(|frame| ReplayFrame {
+                time: frame.time,
+                keys: frame
+                    .keys
+                    .as_lanes()
+                    .into_iter()
+                    .map(|lane| ReplayKeyPressState::from_lane(mapping[lane as usize - 1]).unwrap())
+                    .fold(ReplayKeyPressState::empty(), |keys, k| keys | k),
+            })
+            .collect();
+
+        self.frames = frames;
+        self.normalize_frames();
+        self.header_stale = true;
+
+        Ok(())
+    }
+
+    pub fn mirror_lanes(&mut self, layout: LaneLayout) -> Result<()> {
+        let mapping: Vec<i32> = layout
+            .lanes()
+            .map(|lane| layout.mirror_lane(lane).unwrap())
+            .collect();
+        self.remap_lanes(&mapping)
+    }
+
+    // Appends `other` after this replay, replacing whatever this replay holds from the first
+    // frame of `other` onwards.
+    pub fn splice(&self, other: &QuaverReplay) -> Result<QuaverReplay> {
+        if self.map_md5 != other.map_md5 {
+            return Err(anyhow!(
+                "Replays are for different maps: {} and {}",
+                self.map_md5,
+                other.map_md5
+            ));
+        }
+
+        if self.mode != other.mode || self.mods != other.mods {
+            return Err(anyhow!("Replays were played with different modes or mods"));
+        }
+
+        let other_frames: Vec<ReplayFrame> = other
+            .frames
+            .iter()
+            .filter(|f| f.time != Self::LEADING_FRAME_TIME)
+            .copied()
+            .collect();
+
+        let splice_time = other_frames.first().map(|f| f.time).unwrap_or(i32::MAX);
+
+        let mut replay = self.clone();
+        replay.frames.retain(|f| f.time < splice_time);
+
+        if let Some(last) = replay.frames.last() {
+            if !last.keys.is_empty() {
+                replay.frames.push(ReplayFrame {
+                    time: splice_time - 1,
+                    keys: ReplayKeyPressState::empty(),
+                });
+            }
+        }
+
+        replay.frames.extend(other_frames);
+        replay.pause_count += other.pause_count;
+        replay.normalize_frames();
+        replay.header_stale = true;
+
+        Ok(replay)
+    }
+
+    // Sorts the frames, drops frames that don't change the key state and makes sure the replay
+    // starts with every key up.
+    fn normalize_frames(&mut self) {
+        self.frames.sort_by_key(|f| f.time);
+
+        let leading_time = match self.frames.first() {
+            Some(first) if first.keys.is_empty() => first.time.min(Self::LEADING_FRAME_TIME),
+            Some(first) => first.time.min(Self::LEADING_FRAME_TIME + 1) - 1,
+            None => Self::LEADING_FRAME_TIME,
+        };
+
+        let mut frames: Vec<ReplayFrame> = vec![ReplayFrame {
+            time: leading_time,
+            keys: ReplayKeyPressState::empty(),
+        }];
+
+        for &frame in self.frames.iter() {
+            let last = frames.last_mut().unwrap();
+
+            if frame.time == last.time {
+                last.keys = frame.keys;
+            } else if frame.keys != last.keys {
+                frames.push(frame);
+            }
+        }
+
+        self.frames = frames;
+    }
+}
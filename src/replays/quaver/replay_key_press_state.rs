@@ -29,18 +29,37 @@ bitflags! {
 }
 
 impl ReplayKeyPressState {
+    pub fn from_lane(lane: i32) -> Option<Self> {
+        if (1..=9).contains(&lane) {
+            Self::from_bits(1 << (lane - 1))
+        } else {
+            None
+        }
+    }
+
+    pub fn from_lanes(lanes: &[i32]) -> Option<Self> {
+        lanes.iter().try_fold(Self::empty(), |keys, &lane| {
+            Some(keys | Self::from_lane(lane)?)
+        })
+    }
+
+    pub fn contains_lane(&self, lane: i32) -> bool {
+        Self::from_lane(lane).is_some_and(|keys| self.contains(keys))
+    }
+
+    // Lanes are 1-based, like `HitObjectInfo.lane`.
     pub fn as_lanes(&self) -> Vec<i32> {
         bitmask_to_vec!(
             self,
-            ReplayKeyPressState::K1 => 0,
-            ReplayKeyPressState::K2 => 1,
-            ReplayKeyPressState::K3 => 2,
-            ReplayKeyPressState::K4 => 3,
-            ReplayKeyPressState::K5 => 4,
-            ReplayKeyPressState::K6 => 5,
-            ReplayKeyPressState::K7 => 6,
-            ReplayKeyPressState::K8 => 7,
-            ReplayKeyPressState::K9 => 8
+            ReplayKeyPressState::K1 => 1,
+            ReplayKeyPressState::K2 => 2,
+            ReplayKeyPressState::K3 => 3,
+            ReplayKeyPressState::K4 => 4,
+            ReplayKeyPressState::K5 => 5,
+            ReplayKeyPressState::K6 => 6,
+            ReplayKeyPressState::K7 => 7,
+            ReplayKeyPressState::K8 => 8,
+            ReplayKeyPressState::K9 => 9
         )
     }
 }
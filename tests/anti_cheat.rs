@@ -0,0 +1,253 @@
+use vsrg_utils::maps::QuaverMap;
+use vsrg_utils::replays::quaver::{HumanizedReplaySettings, ReplayFrame, ReplayKeyPressState};
+use vsrg_utils::replays::{AntiCheatDetector, AntiCheatReport, QuaverReplay};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden");
+const MAPS: [&str; 3] = ["4k_stream.qua", "4k_chordjack.qua", "7k_scratch.qua"];
+
+// Replays read back from .qr files. There are no replays of real players in the tree yet, these
+// fixtures were generated with the autoplay and humanized generators.
+const RECORDED: [(&str, &str); 3] = [
+    ("4k_stream_autoplay.qr", "4k_stream.qua"),
+    ("4k_stream_human.qr", "4k_stream.qua"),
+    ("4k_chordjack_speed.qr", "4k_chordjack.qua"),
+];
+
+fn load_map(name: &str) -> QuaverMap {
+    QuaverMap::from_path(&format!("{}/maps/{}", FIXTURES, name))
+}
+
+fn load_replay(name: &str) -> QuaverReplay {
+    QuaverReplay::from_file(&format!("{}/replays/{}", FIXTURES, name))
+}
+
+fn humanized(map: &QuaverMap) -> QuaverReplay {
+    QuaverReplay::generate_humanized_replay(
+        map,
+        &HumanizedReplaySettings {
+            seed: 7,
+            ..Default::default()
+        },
+    )
+}
+
+fn report(replay: &QuaverReplay, map: &QuaverMap) -> AntiCheatReport {
+    AntiCheatReport::from_quaver_replay(replay, map)
+}
+
+#[test]
+fn low_hit_error_variance() {
+    for name in MAPS {
+        let map = load_map(name);
+        let autoplay = QuaverReplay::generate_perfect_replay_keys(&map);
+
+        assert_eq!(
+            report(&autoplay, &map).confidence(AntiCheatDetector::LowHitErrorVariance),
+            1.,
+            "{}",
+            name
+        );
+        assert_eq!(
+            report(&humanized(&map), &map).confidence(AntiCheatDetector::LowHitErrorVariance),
+            0.,
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn uniform_tap_duration() {
+    for name in MAPS {
+        let map = load_map(name);
+        let autoplay = QuaverReplay::generate_perfect_replay_keys(&map);
+
+        assert_eq!(
+            report(&autoplay, &map).confidence(AntiCheatDetector::UniformTapDuration),
+            1.,
+            "{}",
+            name
+        );
+        assert_eq!(
+            report(&humanized(&map), &map).confidence(AntiCheatDetector::UniformTapDuration),
+            0.,
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn autoplay_match() {
+    for name in MAPS {
+        let map = load_map(name);
+        let autoplay = QuaverReplay::generate_perfect_replay_keys(&map);
+
+        assert_eq!(
+            report(&autoplay, &map).confidence(AntiCheatDetector::AutoplayMatch),
+            1.,
+            "{}",
+            name
+        );
+        assert_eq!(
+            report(&humanized(&map), &map).confidence(AntiCheatDetector::AutoplayMatch),
+            0.,
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn timewarp() {
+    for name in MAPS {
+        let map = load_map(name);
+        let autoplay = QuaverReplay::generate_perfect_replay_keys(&map);
+        let humanized = humanized(&map);
+
+        // Played on a clock running 0.2% slow.
+        let mut warped = humanized.clone();
+        for frame in warped.frames.iter_mut() {
+            frame.time = (frame.time as f32 * 1.002).round() as i32;
+        }
+
+        assert_eq!(
+            report(&autoplay, &map).confidence(AntiCheatDetector::Timewarp),
+            0.,
+            "{}",
+            name
+        );
+        assert_eq!(
+            report(&humanized, &map).confidence(AntiCheatDetector::Timewarp),
+            0.,
+            "{}",
+            name
+        );
+        assert!(
+            report(&warped, &map).confidence(AntiCheatDetector::Timewarp) > 0.5,
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn timewarp_far_from_the_claimed_rate() {
+    for name in MAPS {
+        let map = load_map(name);
+        let humanized = humanized(&map);
+
+        // Played at 0.9x and 1.1x while claiming 1.0x, every press drifts out of the match
+        // window within a few seconds.
+        for effective_rate in [0.9, 1.1] {
+            let mut warped = humanized.clone();
+            for frame in warped.frames.iter_mut() {
+                frame.time = (frame.time as f64 / effective_rate).round() as i32;
+            }
+
+            let report = report(&warped, &map);
+            let flag = report
+                .flags
+                .iter()
+                .find(|f| f.detector == AntiCheatDetector::Timewarp)
+                .unwrap();
+
+            assert!(flag.confidence > 0.95, "{} {}", name, effective_rate);
+            assert_eq!(
+                flag.evidence[0],
+                format!("{} presses matched after rescaling", map.hit_objects.len()),
+                "{} {}",
+                name,
+                effective_rate
+            );
+            assert!(
+                flag.evidence[3].ends_with(&format!("effective rate {:.3}x", effective_rate)),
+                "{} {}: {}",
+                name,
+                effective_rate,
+                flag.evidence[3]
+            );
+        }
+    }
+}
+
+#[test]
+fn impossible_input() {
+    for name in MAPS {
+        let map = load_map(name);
+        let autoplay = QuaverReplay::generate_perfect_replay_keys(&map);
+        let humanized = humanized(&map);
+        let key_count = map.get_key_count(None);
+
+        // Presses on a lane the map doesn't have.
+        let mut outside = humanized.clone();
+        let end = outside.frames.last().unwrap().time;
+        for i in 0..5 {
+            let keys = ReplayKeyPressState::from_lane(key_count + 1).unwrap();
+            outside.frames.push(ReplayFrame {
+                time: end + 100 + i * 100,
+                keys,
+            });
+            outside.frames.push(ReplayFrame {
+                time: end + 150 + i * 100,
+                keys: ReplayKeyPressState::empty(),
+            });
+        }
+
+        assert_eq!(
+            report(&autoplay, &map).confidence(AntiCheatDetector::ImpossibleInput),
+            0.,
+            "{}",
+            name
+        );
+        assert_eq!(
+            report(&humanized, &map).confidence(AntiCheatDetector::ImpossibleInput),
+            0.,
+            "{}",
+            name
+        );
+        assert_eq!(
+            report(&outside, &map).confidence(AntiCheatDetector::ImpossibleInput),
+            1.,
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn recorded_replays() {
+    for (replay, map) in RECORDED {
+        let map = load_map(map);
+        let report = report(&load_replay(replay), &map);
+        let is_autoplay = replay.contains("autoplay");
+
+        for detector in [
+            AntiCheatDetector::LowHitErrorVariance,
+            AntiCheatDetector::UniformTapDuration,
+            AntiCheatDetector::AutoplayMatch,
+        ] {
+            let expected = if is_autoplay { 1. } else { 0. };
+            assert_eq!(
+                report.confidence(detector),
+                expected,
+                "{} {:?}",
+                replay,
+                detector
+            );
+        }
+
+        assert_eq!(
+            report.confidence(AntiCheatDetector::Timewarp),
+            0.,
+            "{}",
+            replay
+        );
+        assert_eq!(
+            report.confidence(AntiCheatDetector::ImpossibleInput),
+            0.,
+            "{}",
+            replay
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use vsrg_utils::enums::quaver::ModIdentifier;
+use vsrg_utils::maps::processors::{
+    DifficultyBatch, DifficultyBatchInput, DifficultyProcessor, StrainConstants,
+};
+use vsrg_utils::maps::QuaverMap;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden");
+
+fn map_path(name: &str) -> String {
+    format!("{}/maps/{}", FIXTURES, name)
+}
+
+fn file_md5(path: &str) -> String {
+    format!("{:x}", md5::compute(std::fs::read(path).unwrap()))
+}
+
+#[test]
+fn unset_mods_solve_at_normal_rate() {
+    let path = map_path("4k_stream.qua");
+    let map = QuaverMap::from_path(&path);
+    let expected = DifficultyProcessor::new(
+        &map,
+        StrainConstants::new(),
+        Some(ModIdentifier::empty()),
+        None,
+    )
+    .overall_difficulty;
+
+    for mods in [None, Some(ModIdentifier::None)] {
+        let batch = DifficultyBatch::new(StrainConstants::new(), mods, Some(1));
+        let results = batch.collect([path.as_str()]).unwrap();
+
+        assert_eq!(results[0].overall_difficulty, Ok(expected));
+    }
+}
+
+#[test]
+fn in_memory_maps_use_the_file_md5() {
+    let path = map_path("4k_stream.qua");
+    let mut from_file = QuaverMap::from_path(&path);
+    from_file.file_path = path.clone();
+    let without_file = QuaverMap::from_path(&path);
+
+    let batch = DifficultyBatch::new(StrainConstants::new(), None, Some(1));
+    let inputs: Vec<DifficultyBatchInput> = vec![
+        path.clone().into(),
+        from_file.into(),
+        ("0123456789abcdef".to_string(), without_file.clone()).into(),
+        without_file.into(),
+    ];
+    let results = batch.collect(inputs).unwrap();
+
+    let md5s: Vec<&str> = results.iter().map(|r| r.map_md5.as_str()).collect();
+    let expected = file_md5(&path);
+    assert_eq!(md5s, vec![&expected, &expected, "0123456789abcdef", ""]);
+}
+
+#[test]
+fn failed_maps_do_not_stop_the_batch() {
+    let broken = format!("{}/broken.qua", env!("CARGO_TARGET_TMPDIR"));
+    std::fs::write(&broken, "HitObjects: [").unwrap();
+    let inputs = vec![
+        map_path("4k_stream.qua"),
+        map_path("missing.qua"),
+        broken,
+        map_path("7k_scratch.qua"),
+    ];
+
+    let batch = DifficultyBatch::new(StrainConstants::new(), None, Some(2));
+    let mut last_progress = None;
+    let mut results = Vec::new();
+    batch
+        .run(inputs.clone(), |result, progress| {
+            results.push(result);
+            last_progress = Some(progress);
+        })
+        .unwrap();
+    results.sort_by_key(|r| r.index);
+
+    let progress = last_progress.unwrap();
+    assert_eq!(progress.completed, 4);
+    assert_eq!(progress.failed, 2);
+    assert_eq!(progress.total, Some(4));
+
+    let failed: Vec<bool> = results
+        .iter()
+        .map(|r| r.overall_difficulty.is_err())
+        .collect();
+    assert_eq!(failed, vec![false, true, true, false]);
+    assert!(results.iter().zip(inputs).all(|(r, i)| r.source == i));
+}
+
+#[test]
+fn cancelled_batches_stop_early() {
+    let inputs = vec![map_path("7k_mixed.qua"); 500];
+
+    let batch = DifficultyBatch::new(StrainConstants::new(), None, Some(1));
+    batch.cancel();
+    let progress = batch.run(inputs.clone(), |_, _| {}).unwrap();
+    assert_eq!(progress.completed, 0);
+
+    let batch = DifficultyBatch::new(StrainConstants::new(), None, Some(1));
+    let cancellation = batch.cancellation();
+    let progress = batch
+        .run(inputs, |_, _| {
+            cancellation.store(true, std::sync::atomic::Ordering::Relaxed)
+        })
+        .unwrap();
+
+    assert!(progress.completed >= 1);
+    assert!(progress.completed < 500);
+    assert!(batch.is_cancelled());
+}
@@ -0,0 +1,354 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use vsrg_utils::maps::processors::*;
+use vsrg_utils::maps::QuaverMap;
+use vsrg_utils::replays::QuaverReplay;
+
+// `regression.yaml` is a snapshot of this crate's own output and only reports drift. Run with
+// UPDATE_GOLDEN=1 to rewrite it after an intended algorithm change. `reference.yaml` holds the
+// values Quaver's C# calculators give for the same fixtures and is never rewritten from here.
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden");
+const RATES: [f32; 3] = [0.8, 1.0, 1.5];
+
+const DIFFICULTY_TOLERANCE: f32 = 1e-4;
+const ACCURACY_TOLERANCE: f32 = 1e-4;
+const RATING_TOLERANCE: f32 = 1e-4;
+
+// C# works in single precision too, so only the order of operations should differ.
+const REFERENCE_DIFFICULTY_TOLERANCE: f32 = 1e-3;
+const REFERENCE_ACCURACY_TOLERANCE: f32 = 1e-4;
+const REFERENCE_RATING_TOLERANCE: f32 = 1e-3;
+
+struct Tolerances {
+    difficulty: f32,
+    accuracy: f32,
+    rating: f32,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+struct Expected {
+    version: String,
+    maps: BTreeMap<String, ExpectedMap>,
+    replays: BTreeMap<String, ExpectedReplay>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+struct ExpectedMap {
+    // Rate, formatted as in `RATES`, to overall difficulty.
+    difficulty: BTreeMap<String, f32>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+struct ExpectedReplay {
+    map: String,
+    score: i64,
+    accuracy: f32,
+    max_combo: i64,
+    judgements: BTreeMap<Judgement, i32>,
+    failed: bool,
+    rating: f32,
+}
+
+struct Drift {
+    fixture: String,
+    value: String,
+    expected: String,
+    actual: String,
+}
+
+fn load_map(name: &str) -> QuaverMap {
+    QuaverMap::from_path(&format!("{}/maps/{}", FIXTURES, name))
+}
+
+fn fixture_names(directory: &str) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(format!("{}/{}", FIXTURES, directory))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+fn calculate_map(map: &QuaverMap) -> ExpectedMap {
+    let table =
+        DifficultyProcessor::difficulty_table(map, StrainConstants::new(), Some(&RATES), None);
+
+    ExpectedMap {
+        difficulty: table
+            .rates
+            .iter()
+            .map(|r| (format!("{:.1}", r.rate), r.overall_difficulty))
+            .collect(),
+    }
+}
+
+// Replays are named after their map, `<map>_<anything>.qr`.
+fn calculate_replay(name: &str, maps: &[String]) -> ExpectedReplay {
+    let map_name = maps
+        .iter()
+        .filter(|m| name.starts_with(m.trim_end_matches(".qua")))
+        .max_by_key(|m| m.len())
+        .unwrap_or_else(|| panic!("No map for replay {}", name))
+        .clone();
+
+    let map = load_map(&map_name);
+    let replay = QuaverReplay::from_file(&format!("{}/replays/{}", FIXTURES, name));
+
+    let score_processor = ScoreProcessor::from_map_and_replay(&map, &replay, None);
+    let difficulty =
+        DifficultyProcessor::new(&map, StrainConstants::new(), Some(replay.mods), None);
+    let failed = score_processor.failed();
+
+    ExpectedReplay {
+        map: map_name,
+        score: score_processor.score,
+        accuracy: score_processor.accuracy,
+        max_combo: score_processor.max_combo,
+        judgements: score_processor.current_judgements.clone(),
+        failed,
+        rating: RatingProcessor::new(difficulty.overall_difficulty)
+            .calculate_rating(score_processor.accuracy, Some(failed)),
+    }
+}
+
+fn calculate() -> Expected {
+    let maps = fixture_names("maps");
+
+    Expected {
+        version: DifficultyProcessor::VERSION.to_string(),
+        maps: maps
+            .iter()
+            .map(|name| (name.clone(), calculate_map(&load_map(name))))
+            .collect(),
+        replays: fixture_names("replays")
+            .iter()
+            .map(|name| (name.clone(), calculate_replay(name, &maps)))
+            .collect(),
+    }
+}
+
+fn compare_f32(
+    drifts: &mut Vec<Drift>,
+    fixture: &str,
+    value: &str,
+    expected: f32,
+    actual: f32,
+    tolerance: f32,
+) {
+    if (expected - actual).abs() > tolerance || expected.is_nan() != actual.is_nan() {
+        drifts.push(Drift {
+            fixture: fixture.to_string(),
+            value: value.to_string(),
+            expected: expected.to_string(),
+            actual: format!("{} ({:+})", actual, actual - expected),
+        });
+    }
+}
+
+fn compare<T: PartialEq + std::fmt::Debug>(
+    drifts: &mut Vec<Drift>,
+    fixture: &str,
+    value: &str,
+    expected: T,
+    actual: T,
+) {
+    if expected != actual {
+        drifts.push(Drift {
+            fixture: fixture.to_string(),
+            value: value.to_string(),
+            expected: format!("{:?}", expected),
+            actual: format!("{:?}", actual),
+        });
+    }
+}
+
+fn drifts(expected: &Expected, actual: &Expected, tolerances: &Tolerances) -> Vec<Drift> {
+    let mut drifts = Vec::new();
+
+    compare(
+        &mut drifts,
+        "-",
+        "map fixtures",
+        expected.maps.keys().collect::<Vec<_>>(),
+        actual.maps.keys().collect::<Vec<_>>(),
+    );
+    compare(
+        &mut drifts,
+        "-",
+        "replay fixtures",
+        expected.replays.keys().collect::<Vec<_>>(),
+        actual.replays.keys().collect::<Vec<_>>(),
+    );
+
+    for (name, expected_map) in expected.maps.iter() {
+        let Some(actual_map) = actual.maps.get(name) else {
+            continue;
+        };
+
+        for (rate, &difficulty) in expected_map.difficulty.iter() {
+            let actual_difficulty = actual_map.difficulty.get(rate).copied().unwrap_or(f32::NAN);
+            compare_f32(
+                &mut drifts,
+                name,
+                &format!("difficulty {}x", rate),
+                difficulty,
+                actual_difficulty,
+                tolerances.difficulty,
+            );
+        }
+    }
+
+    for (name, e) in expected.replays.iter() {
+        let Some(a) = actual.replays.get(name) else {
+            continue;
+        };
+
+        compare(&mut drifts, name, "score", e.score, a.score);
+        compare_f32(
+            &mut drifts,
+            name,
+            "accuracy",
+            e.accuracy,
+            a.accuracy,
+            tolerances.accuracy,
+        );
+        compare(&mut drifts, name, "max combo", e.max_combo, a.max_combo);
+        compare(
+            &mut drifts,
+            name,
+            "judgements",
+            &e.judgements,
+            &a.judgements,
+        );
+        compare(&mut drifts, name, "failed", e.failed, a.failed);
+        compare_f32(
+            &mut drifts,
+            name,
+            "rating",
+            e.rating,
+            a.rating,
+            tolerances.rating,
+        );
+    }
+
+    drifts
+}
+
+fn report(drifts: &[Drift], expected: &str, footer: &str) -> String {
+    let mut report = String::new();
+    writeln!(
+        report,
+        "{} value(s) differ from {}:",
+        drifts.len(),
+        expected
+    )
+    .unwrap();
+
+    for drift in drifts {
+        writeln!(
+            report,
+            "  {:<28} {:<18} expected {:<24} got {}",
+            drift.fixture, drift.value, drift.expected, drift.actual
+        )
+        .unwrap();
+    }
+
+    writeln!(report, "{}", footer).unwrap();
+    report
+}
+
+fn write_report(report: &str, name: &str) -> String {
+    let path = format!("{}/{}", env!("CARGO_TARGET_TMPDIR"), name);
+    std::fs::write(&path, report).unwrap();
+    path
+}
+
+#[test]
+fn regression_snapshot_drift() {
+    let expected_path = format!("{}/regression.yaml", FIXTURES);
+    let actual = calculate();
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&expected_path, serde_yaml::to_string(&actual).unwrap()).unwrap();
+        return;
+    }
+
+    let expected: Expected =
+        serde_yaml::from_str(&std::fs::read_to_string(&expected_path).unwrap()).unwrap();
+
+    let mut drifts = drifts(
+        &expected,
+        &actual,
+        &Tolerances {
+            difficulty: DIFFICULTY_TOLERANCE,
+            accuracy: ACCURACY_TOLERANCE,
+            rating: RATING_TOLERANCE,
+        },
+    );
+    compare(
+        &mut drifts,
+        "-",
+        "version",
+        &expected.version,
+        &actual.version,
+    );
+
+    if !drifts.is_empty() {
+        let report = report(
+            &drifts,
+            "the regression snapshot",
+            "Rerun with UPDATE_GOLDEN=1 if the change is intended.",
+        );
+        let report_path = write_report(&report, "regression_report.txt");
+
+        panic!("{}Report written to {}", report, report_path);
+    }
+}
+
+// Parity with Quaver. Ignored until `reference.yaml` has been generated with the C# calculators,
+// see the fixtures README.
+#[test]
+#[ignore = "reference.yaml hasn't been generated with Quaver's C# calculators yet"]
+fn reference_outputs_match() {
+    let reference_path = format!("{}/reference.yaml", FIXTURES);
+    let reference = std::fs::read_to_string(&reference_path)
+        .unwrap_or_else(|e| panic!("Can't read {}: {}", reference_path, e));
+    let reference: Expected = serde_yaml::from_str(&reference).unwrap();
+
+    let drifts = drifts(
+        &reference,
+        &calculate(),
+        &Tolerances {
+            difficulty: REFERENCE_DIFFICULTY_TOLERANCE,
+            accuracy: REFERENCE_ACCURACY_TOLERANCE,
+            rating: REFERENCE_RATING_TOLERANCE,
+        },
+    );
+
+    if !drifts.is_empty() {
+        let report = report(
+            &drifts,
+            &format!("Quaver {}", reference.version),
+            "Reference values are never rewritten from this crate.",
+        );
+        let report_path = write_report(&report, "reference_report.txt");
+
+        panic!("{}Report written to {}", report, report_path);
+    }
+}
+
+#[test]
+fn replay_fixtures_round_trip() {
+    for name in fixture_names("replays") {
+        let path = format!("{}/replays/{}", FIXTURES, name);
+        let replay = QuaverReplay::from_file(&path);
+        let bytes = replay.to_bytes().unwrap();
+
+        assert_eq!(bytes, std::fs::read(&path).unwrap(), "{}", name);
+    }
+}
@@ -0,0 +1,129 @@
+use vsrg_utils::enums::quaver::{LaneLayout, ModIdentifier};
+use vsrg_utils::maps::processors::structures::{FingerState, Hand, HandLayout};
+use vsrg_utils::maps::processors::{DifficultyProcessor, StrainConstants};
+use vsrg_utils::maps::QuaverMap;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden");
+
+fn load_map(name: &str) -> QuaverMap {
+    QuaverMap::from_path(&format!("{}/maps/{}", FIXTURES, name))
+}
+
+fn validation_error(hand_layout: HandLayout, layout: LaneLayout) -> String {
+    hand_layout
+        .validate(layout)
+        .expect_err("layout should be rejected")
+        .to_string()
+}
+
+fn solve(map: &QuaverMap, hand_layout: HandLayout) -> anyhow::Result<f32> {
+    DifficultyProcessor::with_hand_layout(
+        map,
+        StrainConstants::new(),
+        hand_layout,
+        Some(ModIdentifier::empty()),
+        None,
+    )
+    .map(|processor| processor.overall_difficulty)
+}
+
+#[test]
+fn default_layouts_are_valid() {
+    for layout in [
+        LaneLayout::Keys4,
+        LaneLayout::Keys4Scratch,
+        LaneLayout::Keys7,
+        LaneLayout::Keys7Scratch,
+    ] {
+        assert!(HandLayout::default_for(layout).validate(layout).is_ok());
+    }
+}
+
+#[test]
+fn unknown_lanes_are_rejected() {
+    let hand_layout =
+        HandLayout::default_for(LaneLayout::Keys4).with_lane(5, Hand::Right, FingerState::Ring);
+
+    assert_eq!(
+        validation_error(hand_layout, LaneLayout::Keys4),
+        "Lane 5 is outside of the 4 key layout"
+    );
+}
+
+#[test]
+fn scratch_is_only_allowed_on_the_scratch_lane() {
+    let scratch_finger =
+        HandLayout::default_for(LaneLayout::Keys4).with_lane(1, Hand::Left, FingerState::Scratch);
+    let scratch_hand = HandLayout::default_for(LaneLayout::Keys4).with_lane(
+        2,
+        Hand::Scratch,
+        FingerState::Scratch,
+    );
+
+    assert_eq!(
+        validation_error(scratch_finger, LaneLayout::Keys4),
+        "Only the scratch lane can be played as scratch, not lane 1"
+    );
+    assert_eq!(
+        validation_error(scratch_hand, LaneLayout::Keys4),
+        "Only the scratch lane can be played as scratch, not lane 2"
+    );
+
+    // The scratch lane can be played by either hand, but the scratch finger belongs to the
+    // scratch hand.
+    let left_pinkie = HandLayout::default_for(LaneLayout::Keys7Scratch).with_lane(
+        8,
+        Hand::Left,
+        FingerState::Pinkie,
+    );
+    let left_scratch = HandLayout::default_for(LaneLayout::Keys7Scratch).with_lane(
+        8,
+        Hand::Left,
+        FingerState::Scratch,
+    );
+
+    assert!(left_pinkie.validate(LaneLayout::Keys7Scratch).is_ok());
+    assert_eq!(
+        validation_error(left_scratch, LaneLayout::Keys7Scratch),
+        "Lane 8 must use the scratch finger on the scratch hand, got Scratch on Left"
+    );
+}
+
+#[test]
+fn layout_must_match_the_map_key_count() {
+    let keys7 = load_map("7k_mixed.qua");
+    let keys4 = load_map("4k_stream.qua");
+
+    let error = solve(&keys7, HandLayout::default_for(LaneLayout::Keys4))
+        .err()
+        .unwrap();
+    assert_eq!(error.to_string(), "No hand mapping for lane 5");
+
+    let error = solve(&keys4, HandLayout::default_for(LaneLayout::Keys7))
+        .err()
+        .unwrap();
+    assert_eq!(error.to_string(), "Lane 5 is outside of the 4 key layout");
+}
+
+#[test]
+fn custom_thumb_changes_the_rating() {
+    let map = load_map("7k_mixed.qua");
+
+    let default = solve(&map, HandLayout::default_for(LaneLayout::Keys7)).unwrap();
+    let left_thumb = solve(&map, HandLayout::keys7_thumb(Hand::Left)).unwrap();
+    let right_thumb = solve(&map, HandLayout::keys7_thumb(Hand::Right)).unwrap();
+
+    assert_eq!(
+        default,
+        DifficultyProcessor::new(
+            &map,
+            StrainConstants::new(),
+            Some(ModIdentifier::empty()),
+            None
+        )
+        .overall_difficulty
+    );
+    assert_ne!(left_thumb, default);
+    assert_ne!(right_thumb, default);
+    assert_ne!(left_thumb, right_thumb);
+}
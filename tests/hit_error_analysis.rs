@@ -0,0 +1,192 @@
+use vsrg_utils::maps::processors::{
+    HitErrorAnalysis, HitErrorDistribution, HitErrorDriftPoint, HitErrorHistogramBucket, HitStat,
+    HitStatType, KeyPressType,
+};
+use vsrg_utils::maps::structures::HitObjectInfo;
+
+fn stat(
+    type_: HitStatType,
+    key_press_type: KeyPressType,
+    lane: i32,
+    song_position: i32,
+    hit_difference: i32,
+) -> HitStat {
+    let hit_object = HitObjectInfo {
+        start_time: song_position + hit_difference,
+        lane,
+        end_time: 0,
+    };
+
+    HitStat::new(
+        type_,
+        key_press_type,
+        Some(hit_object),
+        Some(song_position),
+        None,
+        hit_difference,
+        None,
+        None,
+    )
+}
+
+fn press(lane: i32, song_position: i32, hit_difference: i32) -> HitStat {
+    stat(
+        HitStatType::HIT,
+        KeyPressType::PRESS,
+        lane,
+        song_position,
+        hit_difference,
+    )
+}
+
+fn release(lane: i32, song_position: i32, hit_difference: i32) -> HitStat {
+    stat(
+        HitStatType::HIT,
+        KeyPressType::RELEASE,
+        lane,
+        song_position,
+        hit_difference,
+    )
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-4,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn distribution_of_known_offsets() {
+    let distribution = HitErrorDistribution::from_offsets(&[10., -10., 20., -20., 0.]);
+
+    // Variance is (100 + 100 + 400 + 400 + 0) / 5 = 200.
+    assert_eq!(distribution.count, 5);
+    assert_close(distribution.mean, 0.);
+    assert_close(distribution.standard_deviation, 200f32.sqrt());
+    assert_close(distribution.unstable_rate, 200f32.sqrt() * 10.);
+
+    let shifted = HitErrorDistribution::from_offsets(&[-4., -6.]);
+    assert_close(shifted.mean, -5.);
+    assert_close(shifted.standard_deviation, 1.);
+    assert_close(shifted.unstable_rate, 10.);
+
+    assert_eq!(
+        HitErrorDistribution::from_offsets(&[]),
+        HitErrorDistribution::default()
+    );
+}
+
+#[test]
+fn presses_and_releases_are_split() {
+    let analysis = HitErrorAnalysis::new(&[
+        press(1, 1000, 10),
+        press(2, 1200, 20),
+        release(1, 1400, -30),
+        // Misses and releases without a hit don't count.
+        stat(HitStatType::MISS, KeyPressType::PRESS, 3, 1600, -180),
+        release(2, 1800, i32::MIN),
+    ]);
+
+    assert_eq!(analysis.hits.len(), 3);
+    assert_eq!(analysis.overall.count, 3);
+    assert_close(analysis.overall.mean, 0.);
+
+    assert_eq!(analysis.press.count, 2);
+    assert_close(analysis.press.mean, 15.);
+    assert_close(analysis.press.standard_deviation, 5.);
+
+    assert_eq!(analysis.release.count, 1);
+    assert_close(analysis.release.mean, -30.);
+    assert_close(analysis.release.standard_deviation, 0.);
+
+    assert_eq!(analysis.early_count(), 2);
+    assert_eq!(analysis.late_count(), 1);
+}
+
+#[test]
+fn lane_bias_is_per_lane() {
+    let analysis = HitErrorAnalysis::new(&[
+        press(1, 1000, 10),
+        press(2, 1100, -8),
+        press(1, 1200, 20),
+        press(2, 1300, -12),
+        release(4, 1400, 3),
+    ]);
+
+    let lanes: Vec<(i32, usize, f32)> = analysis
+        .lane_bias
+        .iter()
+        .map(|(&lane, d)| (lane, d.count, d.mean))
+        .collect();
+
+    assert_eq!(lanes, vec![(1, 2, 15.), (2, 2, -10.), (4, 1, 3.)]);
+}
+
+#[test]
+fn histogram_bucket_edges_and_clamping() {
+    let offsets = [-100, -30, -21, -20, 0, 9, 10, 29, 30, 100];
+    let stats: Vec<HitStat> = offsets
+        .iter()
+        .enumerate()
+        .map(|(i, &offset)| press(1, i as i32 * 100, offset))
+        .collect();
+    let analysis = HitErrorAnalysis::new(&stats);
+
+    let bucket = |start, end, count| HitErrorHistogramBucket { start, end, count };
+
+    // Starts are inclusive, ends exclusive. Offsets past either side land in the outer buckets.
+    assert_eq!(
+        analysis.histogram(10, 30),
+        vec![
+            bucket(-30, -20, 3),
+            bucket(-20, -10, 1),
+            bucket(-10, 0, 0),
+            bucket(0, 10, 2),
+            bucket(10, 20, 1),
+            bucket(20, 30, 3),
+        ]
+    );
+
+    // The last bucket is cut off at the range when the size doesn't divide it.
+    assert_eq!(
+        analysis.histogram(15, 20),
+        vec![bucket(-20, -5, 4), bucket(-5, 10, 2), bucket(10, 20, 4)]
+    );
+
+    assert!(analysis.histogram(0, 30).is_empty());
+    assert!(analysis.histogram(10, 0).is_empty());
+}
+
+#[test]
+fn drift_windows() {
+    // Out of order on purpose, the analysis sorts by song position.
+    let analysis = HitErrorAnalysis::new(&[
+        press(1, 4500, 4),
+        press(1, 1000, 10),
+        press(2, 2100, -10),
+        press(2, 1999, 20),
+    ]);
+
+    let point = |start_time, end_time, mean, count| HitErrorDriftPoint {
+        start_time,
+        end_time,
+        mean,
+        count,
+    };
+
+    // Windows start at the first hit, the empty 3000..4000 window is left out.
+    assert_eq!(
+        analysis.drift(1000),
+        vec![
+            point(1000, 2000, 15., 2),
+            point(2000, 3000, -10., 1),
+            point(4000, 5000, 4., 1),
+        ]
+    );
+
+    assert!(analysis.drift(0).is_empty());
+    assert!(HitErrorAnalysis::new(&[]).drift(1000).is_empty());
+}
@@ -0,0 +1,93 @@
+use vsrg_utils::maps::processors::ScoreProcessor;
+use vsrg_utils::maps::QuaverMap;
+use vsrg_utils::replays::quaver::{HumanizedReplaySettings, OffsetDistribution};
+use vsrg_utils::replays::QuaverReplay;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden");
+const MAPS: [&str; 3] = ["4k_stream.qua", "4k_chordjack.qua", "7k_scratch.qua"];
+
+fn load_map(name: &str) -> QuaverMap {
+    QuaverMap::from_path(&format!("{}/maps/{}", FIXTURES, name))
+}
+
+fn frames(replay: &QuaverReplay) -> Vec<(i32, i16)> {
+    replay
+        .frames
+        .iter()
+        .map(|f| (f.time, f.keys.bits()))
+        .collect()
+}
+
+fn accuracy(map: &QuaverMap, settings: &HumanizedReplaySettings) -> f32 {
+    let replay = QuaverReplay::generate_humanized_replay(map, settings);
+    ScoreProcessor::from_map_and_replay(map, &replay, None).accuracy
+}
+
+#[test]
+fn same_seed_generates_the_same_frames() {
+    for name in MAPS {
+        let map = load_map(name);
+        let settings = HumanizedReplaySettings {
+            seed: 42,
+            chord_split_ms: 10.,
+            miss_rate: 0.02,
+            ghost_tap_rate: 0.05,
+            ..Default::default()
+        };
+
+        let first = QuaverReplay::generate_humanized_replay(&map, &settings);
+        let second = QuaverReplay::generate_humanized_replay(&map, &settings);
+        let other_seed = QuaverReplay::generate_humanized_replay(
+            &map,
+            &HumanizedReplaySettings {
+                seed: 43,
+                ..settings.clone()
+            },
+        );
+
+        assert_eq!(frames(&first), frames(&second), "{}", name);
+        assert_ne!(frames(&first), frames(&other_seed), "{}", name);
+    }
+}
+
+#[test]
+fn scored_accuracy_is_in_the_target_range() {
+    // Standard deviation of the press offset and the accuracy range it should score in.
+    let targets = [
+        (5., 99.5, 100.),
+        (15., 98.5, 100.),
+        (30., 90., 96.),
+        (50., 70., 85.),
+    ];
+
+    for name in MAPS {
+        let map = load_map(name);
+
+        for (standard_deviation, lowest, highest) in targets {
+            for seed in 0..4 {
+                let settings = HumanizedReplaySettings {
+                    seed,
+                    press_offset: OffsetDistribution::Gaussian {
+                        mean: 0.,
+                        standard_deviation,
+                    },
+                    release_offset: OffsetDistribution::Gaussian {
+                        mean: 0.,
+                        standard_deviation,
+                    },
+                    ..Default::default()
+                };
+                let accuracy = accuracy(&map, &settings);
+
+                assert!(
+                    (lowest..=highest).contains(&accuracy),
+                    "{} with {} ms deviation, seed {}: {}",
+                    name,
+                    standard_deviation,
+                    seed,
+                    accuracy
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use vsrg_utils::enums::osu;
+use vsrg_utils::enums::quaver::ModIdentifier;
+use vsrg_utils::maps::processors::{Judgement, JudgementWindows, OsuHitWindows, ScoreProcessor};
+use vsrg_utils::maps::QuaverMap;
+
+fn windows_of(processor: &ScoreProcessor) -> [f32; 6] {
+    [
+        Judgement::Marv,
+        Judgement::Perf,
+        Judgement::Great,
+        Judgement::Good,
+        Judgement::Okay,
+        Judgement::Miss,
+    ]
+    .map(|j| processor.judgement_window[&j])
+}
+
+fn values(windows: &JudgementWindows) -> [f32; 6] {
+    [
+        windows.marvelous,
+        windows.perfect,
+        windows.great,
+        windows.good,
+        windows.okay,
+        windows.miss,
+    ]
+}
+
+#[test]
+fn strict_and_chill_mods_use_their_presets() {
+    let map = QuaverMap::default();
+
+    for (mods, preset) in [
+        (ModIdentifier::empty(), JudgementWindows::standard()),
+        (ModIdentifier::Strict, JudgementWindows::strict()),
+        (ModIdentifier::Chill, JudgementWindows::chill()),
+    ] {
+        let processor = ScoreProcessor::from_map(&map, mods, None);
+
+        assert_eq!(windows_of(&processor), values(&preset), "{}", preset.name);
+        assert_eq!(
+            values(&JudgementWindows::standard().with_mods(mods)),
+            values(&preset),
+            "{}",
+            preset.name
+        );
+    }
+}
+
+#[test]
+fn strict_and_chill_combine_with_rate() {
+    let map = QuaverMap::default();
+    let processor =
+        ScoreProcessor::from_map(&map, ModIdentifier::Strict | ModIdentifier::Speed15X, None);
+
+    assert_eq!(
+        windows_of(&processor),
+        values(&JudgementWindows::strict().scaled(1.5))
+    );
+}
+
+#[test]
+fn osu_overall_difficulty_windows() {
+    for od in [0., 5., 8., 8.5, 10.] {
+        let windows = JudgementWindows::from_osu_overall_difficulty(od);
+        let osu = OsuHitWindows::from_overall_difficulty(od, osu::ModIdentifier::empty());
+
+        assert_eq!(
+            values(&windows),
+            [osu.max, osu.hit300, osu.hit200, osu.hit100, osu.hit50, osu.miss],
+            "OD{}",
+            od
+        );
+        assert_eq!(windows.name, format!("osu! OD{}", od));
+        assert_eq!(windows.combo_break_judgement, Judgement::Miss);
+        assert!(!windows.is_default);
+    }
+
+    assert_eq!(
+        values(&JudgementWindows::from_osu_overall_difficulty(8.)),
+        [16., 40., 73., 103., 127., 164.]
+    );
+    assert_eq!(JudgementWindows::from_osu_overall_difficulty(8.5).id, 88);
+}
+
+#[test]
+fn preset_ids_and_names() {
+    let presets: Vec<(i32, String)> = JudgementWindows::presets()
+        .into_iter()
+        .map(|w| (w.id, w.name))
+        .collect();
+
+    let mut expected = vec![
+        (0, "Standard".to_string()),
+        (1, "Strict".to_string()),
+        (2, "Chill".to_string()),
+    ];
+    expected.extend((0..=10).map(|od| (3 + od * 10, format!("osu! OD{}", od))));
+
+    assert_eq!(presets, expected);
+
+    assert_eq!(
+        JudgementWindows::preset("strict"),
+        Some(JudgementWindows::strict())
+    );
+    assert_eq!(
+        JudgementWindows::preset("osu! od7"),
+        Some(JudgementWindows::from_osu_overall_difficulty(7.))
+    );
+    assert_eq!(JudgementWindows::preset("Missing"), None);
+}
+
+#[test]
+fn windows_round_trip() {
+    let sets = vec![
+        JudgementWindows::standard(),
+        JudgementWindows::chill().scaled(1.5),
+        JudgementWindows::from_osu_overall_difficulty(8.5),
+    ];
+
+    let read = JudgementWindows::from_string(&JudgementWindows::to_string(&sets).unwrap());
+    assert_eq!(read.unwrap(), sets);
+
+    let path = format!("{}/judgement_windows.yaml", env!("CARGO_TARGET_TMPDIR"));
+    JudgementWindows::to_file(&sets, &path).unwrap();
+    assert_eq!(JudgementWindows::from_path(&path).unwrap(), sets);
+
+    // Fields missing from a file keep the standard windows.
+    let partial = JudgementWindows::from_string("- Name: Tight\n  Marvelous: 10\n");
+    assert_eq!(
+        partial.unwrap(),
+        vec![JudgementWindows {
+            name: "Tight".to_string(),
+            marvelous: 10.,
+            ..JudgementWindows::default()
+        }]
+    );
+}
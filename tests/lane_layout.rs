@@ -0,0 +1,153 @@
+use vsrg_utils::enums::quaver::{GameMode, LaneLayout};
+use vsrg_utils::maps::structures::HitObjectInfo;
+use vsrg_utils::maps::QuaverMap;
+use vsrg_utils::replays::osu::KeyPressState;
+use vsrg_utils::replays::quaver::ReplayKeyPressState;
+use vsrg_utils::replays::{OsuReplay, QuaverReplay};
+
+const LAYOUTS: [LaneLayout; 4] = [
+    LaneLayout::Keys4,
+    LaneLayout::Keys4Scratch,
+    LaneLayout::Keys7,
+    LaneLayout::Keys7Scratch,
+];
+
+fn map_with_every_lane(layout: LaneLayout) -> QuaverMap {
+    QuaverMap {
+        mode: layout.mode(),
+        has_scratch_key: layout.has_scratch_key(),
+        hit_objects: layout
+            .lanes()
+            .map(|lane| HitObjectInfo {
+                start_time: lane * 1000,
+                lane,
+                end_time: 0,
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn layout_matches_map() {
+    for layout in LAYOUTS {
+        let map = map_with_every_lane(layout);
+
+        assert_eq!(map.lane_layout(), layout);
+        assert_eq!(layout.key_count(), map.get_key_count(None));
+        assert_eq!(layout.lanes().count() as i32, layout.key_count());
+    }
+
+    assert_eq!(
+        LaneLayout::new(GameMode::Keys7, true),
+        LaneLayout::Keys7Scratch
+    );
+    assert_eq!(
+        LaneLayout::from_key_count(8),
+        Some(LaneLayout::Keys7Scratch)
+    );
+    assert_eq!(LaneLayout::from_key_count(5), None);
+}
+
+#[test]
+fn scratch_is_the_last_lane() {
+    assert_eq!(LaneLayout::Keys4.scratch_lane(), None);
+    assert_eq!(LaneLayout::Keys7.scratch_lane(), None);
+    assert_eq!(LaneLayout::Keys4Scratch.scratch_lane(), Some(5));
+    assert_eq!(LaneLayout::Keys7Scratch.scratch_lane(), Some(8));
+    assert!(LaneLayout::Keys7Scratch.is_scratch_lane(8));
+    assert!(!LaneLayout::Keys7Scratch.is_scratch_lane(1));
+}
+
+#[test]
+fn replay_key_state_round_trips_lanes() {
+    for layout in LAYOUTS {
+        for lane in layout.lanes() {
+            let keys = ReplayKeyPressState::from_lane(lane).unwrap();
+
+            assert_eq!(keys.bits(), 1 << (lane - 1));
+            assert_eq!(keys.as_lanes(), vec![lane]);
+            assert!(keys.contains_lane(lane));
+        }
+
+        let all: Vec<i32> = layout.lanes().collect();
+        assert_eq!(
+            ReplayKeyPressState::from_lanes(&all).unwrap().as_lanes(),
+            all
+        );
+    }
+
+    assert_eq!(ReplayKeyPressState::from_lane(0), None);
+    assert_eq!(ReplayKeyPressState::from_lane(10), None);
+}
+
+#[test]
+fn osu_columns_round_trip() {
+    for layout in LAYOUTS {
+        for lane in layout.lanes() {
+            let column = layout.lane_to_osu_column(lane).unwrap();
+            assert_eq!(layout.osu_column_to_lane(column), Some(lane));
+        }
+
+        assert_eq!(layout.osu_column_to_lane(layout.key_count() + 1), None);
+    }
+
+    assert_eq!(LaneLayout::Keys7.lane_to_osu_column(1), Some(1));
+    assert_eq!(LaneLayout::Keys7Scratch.lane_to_osu_column(8), Some(1));
+    assert_eq!(LaneLayout::Keys7Scratch.lane_to_osu_column(1), Some(2));
+}
+
+#[test]
+fn autoplay_presses_every_lane_of_the_layout() {
+    for layout in LAYOUTS {
+        let map = map_with_every_lane(layout);
+        let replay = QuaverReplay::generate_perfect_replay_keys(&map);
+
+        for hit_object in map.hit_objects.iter() {
+            let frame = replay
+                .frames
+                .iter()
+                .find(|f| f.time == hit_object.start_time)
+                .unwrap();
+
+            assert_eq!(frame.keys.as_lanes(), vec![hit_object.lane]);
+        }
+    }
+}
+
+#[test]
+fn osu_conversion_keeps_lanes() {
+    for layout in [
+        LaneLayout::Keys4,
+        LaneLayout::Keys7,
+        LaneLayout::Keys7Scratch,
+    ] {
+        let map = map_with_every_lane(layout);
+        let replay = QuaverReplay::generate_perfect_replay_keys(&map);
+
+        let osu = OsuReplay::from_quaver_replay(&replay, layout.key_count()).replay;
+        let pressed_columns: Vec<i32> = osu
+            .frames()
+            .filter(|f| !f.keys.is_empty())
+            .flat_map(|f| f.keys.as_lanes(layout.key_count()))
+            .collect();
+        let expected_columns: Vec<i32> = layout
+            .lanes()
+            .map(|lane| layout.lane_to_osu_column(lane).unwrap())
+            .collect();
+        assert_eq!(pressed_columns, expected_columns);
+
+        let back = QuaverReplay::from_osu_replay(&osu, layout.key_count())
+            .unwrap()
+            .replay;
+        let pressed_lanes: Vec<i32> = back
+            .frames
+            .iter()
+            .filter(|f| !f.keys.is_empty())
+            .flat_map(|f| f.keys.as_lanes())
+            .collect();
+        assert_eq!(pressed_lanes, layout.lanes().collect::<Vec<_>>());
+    }
+
+    assert_eq!(KeyPressState::K1.as_lanes(8), vec![1]);
+}
@@ -0,0 +1,145 @@
+use vsrg_utils::enums::osu::ModIdentifier;
+use vsrg_utils::maps::parsers::OsuBeatmap;
+use vsrg_utils::maps::processors::ScoreProcessor;
+use vsrg_utils::maps::QuaverMap;
+use vsrg_utils::replays::osu::{
+    KeyPressState, LifeBarGraph, ReplayEvent, ReplayLaneEvent, ReplayLaneEventType,
+};
+use vsrg_utils::replays::{OsuReplay, QuaverReplay};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden");
+
+fn processed_fixture() -> (QuaverReplay, ScoreProcessor) {
+    let map = QuaverMap::from_path(&format!("{}/maps/4k_stream.qua", FIXTURES));
+    let replay = QuaverReplay::from_file(&format!("{}/replays/4k_stream_human.qr", FIXTURES));
+    let processor = ScoreProcessor::from_map_and_replay(&map, &replay, None);
+
+    (replay, processor)
+}
+
+#[test]
+fn life_bar_is_built_from_the_health_timeline() {
+    let (_, processor) = processed_fixture();
+    let graph = LifeBarGraph::from_score_processor(&processor);
+
+    assert!(!graph.samples.is_empty());
+    assert_eq!(graph.samples.len(), processor.health_timeline.len());
+
+    for (sample, health) in graph.samples.iter().zip(processor.health_timeline.iter()) {
+        assert_eq!(sample.time, health.time);
+        assert_eq!(sample.health, health.health / 100.);
+    }
+}
+
+#[test]
+fn written_replays_include_the_life_bar() {
+    let (replay, processor) = processed_fixture();
+    let graph = LifeBarGraph::from_score_processor(&processor).resample(200);
+
+    let mut osu_replay = OsuReplay::from_quaver_replay(&replay, 4).replay;
+    osu_replay.set_life_bar_graph(&graph);
+    osu_replay.rng_seed = 1234;
+
+    let path = format!("{}/life_bar.osr", env!("CARGO_TARGET_TMPDIR"));
+    osu_replay.to_file(&path).unwrap();
+    let read = OsuReplay::from_path(&path).unwrap();
+
+    assert_eq!(read.life_bar, osu_replay.life_bar);
+    assert_eq!(read.life_bar_graph().unwrap(), graph);
+    assert_eq!(read.rng_seed, 1234);
+    assert_eq!(read.map_md5, osu_replay.map_md5);
+    assert_eq!(read.mods, osu_replay.mods);
+    assert_eq!(
+        read.frames().map(|f| (f.time, f.keys)).collect::<Vec<_>>(),
+        osu_replay
+            .frames()
+            .map(|f| (f.time, f.keys))
+            .collect::<Vec<_>>()
+    );
+}
+
+fn events(deltas: &[(i64, u32)]) -> Vec<ReplayEvent> {
+    deltas
+        .iter()
+        .map(|&(time_delta, bits)| ReplayEvent {
+            time_delta,
+            keys: KeyPressState::from_bits_truncate(bits),
+        })
+        .collect()
+}
+
+#[test]
+fn key_count_prefers_the_key_mod() {
+    let beatmap = OsuBeatmap {
+        key_count: 7,
+        ..Default::default()
+    };
+    let no_keys = OsuBeatmap::default();
+
+    let mut replay = OsuReplay::default();
+    assert_eq!(replay.key_count(None), None);
+    assert_eq!(replay.key_count(Some(&beatmap)), Some(7));
+    assert_eq!(replay.key_count(Some(&no_keys)), None);
+
+    replay.mods = ModIdentifier::Key4 | ModIdentifier::Mirror;
+    assert_eq!(replay.key_count(None), Some(4));
+    assert_eq!(replay.key_count(Some(&beatmap)), Some(4));
+}
+
+#[test]
+fn lane_events_follow_key_changes() {
+    let replay = OsuReplay {
+        replay_data: events(&[(0, 0), (100, 0b0001), (50, 0b0101), (30, 0b0100), (20, 0)]),
+        ..Default::default()
+    };
+
+    let press = |time, lane| ReplayLaneEvent {
+        time,
+        lane,
+        type_: ReplayLaneEventType::Press,
+    };
+    let release = |time, lane| ReplayLaneEvent {
+        time,
+        lane,
+        type_: ReplayLaneEventType::Release,
+    };
+
+    assert_eq!(
+        replay.lane_events(4),
+        vec![
+            press(100, 1),
+            press(150, 3),
+            release(180, 1),
+            release(200, 3)
+        ]
+    );
+
+    // Lanes past the key count are ignored.
+    assert_eq!(replay.lane_events(2), vec![press(100, 1), release(180, 1)]);
+}
+
+#[test]
+fn only_the_last_frame_holds_the_seed() {
+    let replay = OsuReplay {
+        mode: 3,
+        replay_data: events(&[(0, 0), (-12345, 0b0010), (200, 0b0001), (40, 0)]),
+        rng_seed: 98765,
+        ..Default::default()
+    };
+
+    let path = format!("{}/seed_frames.osr", env!("CARGO_TARGET_TMPDIR"));
+    replay.to_file(&path).unwrap();
+    let read = OsuReplay::from_path(&path).unwrap();
+
+    // A -12345 delta before the end is an ordinary frame.
+    let deltas: Vec<(i64, u32)> = read
+        .replay_data
+        .iter()
+        .map(|e| (e.time_delta, e.keys.bits()))
+        .collect();
+    assert_eq!(
+        deltas,
+        vec![(0, 0), (-12345, 0b0010), (200, 0b0001), (40, 0)]
+    );
+    assert_eq!(read.rng_seed, 98765);
+}
@@ -0,0 +1,78 @@
+use vsrg_utils::enums::osu::ModIdentifier;
+use vsrg_utils::maps::parsers::OsuBeatmap;
+use vsrg_utils::maps::processors::{OsuGrade, OsuScoreProcessor};
+use vsrg_utils::replays::OsuReplay;
+
+// A hand made 4K map at OD 8 and two replays of it. The counts, combo and score stored in the
+// replays were worked out by hand from the judgement rules, see `tests/fixtures/osu/README.md`.
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/osu");
+
+fn process(replay: &OsuReplay) -> OsuScoreProcessor {
+    let beatmap = OsuBeatmap::from_path(&format!("{}/4k_mixed.osu", FIXTURES));
+    let mut processor = OsuScoreProcessor::from_beatmap(&beatmap, replay.mods);
+    processor.process_replay(&beatmap, replay);
+    processor
+}
+
+fn load_replay(name: &str) -> OsuReplay {
+    OsuReplay::from_path(&format!("{}/{}", FIXTURES, name)).unwrap()
+}
+
+#[test]
+fn score_v1_matches_the_replay() {
+    let replay = load_replay("4k_mixed_v1.osr");
+    assert!(!replay.mods.contains(ModIdentifier::ScoreV2));
+
+    let processor = process(&replay);
+
+    assert_eq!(processor.validate_against_replay(&replay), vec![]);
+    assert_eq!(processor.max_combo, 4);
+    assert_eq!(processor.score, 454828);
+}
+
+#[test]
+fn score_v2_matches_the_replay() {
+    let replay = load_replay("4k_mixed_v2.osr");
+    assert!(replay.mods.contains(ModIdentifier::ScoreV2));
+
+    let processor = process(&replay);
+
+    assert_eq!(processor.validate_against_replay(&replay), vec![]);
+    assert_eq!(processor.max_combo, 6);
+    assert_eq!(processor.score, 677902);
+}
+
+#[test]
+fn mismatches_are_reported() {
+    let mut replay = load_replay("4k_mixed_v1.osr");
+    replay.count_miss += 1;
+    replay.max_combo = 7;
+
+    let mismatches = process(&replay).validate_against_replay(&replay);
+    let fields: Vec<&str> = mismatches.iter().map(|m| m.field.as_str()).collect();
+
+    assert_eq!(fields, vec!["count_miss", "max_combo"]);
+    assert_eq!(mismatches[0].expected, 2);
+    assert_eq!(mismatches[0].actual, 1);
+}
+
+#[test]
+fn grade_uses_the_failed_state() {
+    let mut replay = load_replay("4k_mixed_v1.osr");
+    let processor = process(&replay);
+
+    assert!(!processor.failed);
+    assert_ne!(processor.grade(), OsuGrade::F);
+
+    replay.life_bar = "0|1,1764|0,3320|0,".to_string();
+    let processor = process(&replay);
+
+    assert!(processor.failed);
+    assert_eq!(processor.grade(), OsuGrade::F);
+
+    replay.mods |= ModIdentifier::NoFail;
+    let processor = process(&replay);
+
+    assert!(!processor.failed);
+    assert_ne!(processor.grade(), OsuGrade::F);
+}
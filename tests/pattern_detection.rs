@@ -0,0 +1,196 @@
+use vsrg_utils::enums::quaver::{GameMode, ModIdentifier, QssPatternFlags};
+use vsrg_utils::maps::processors::{DifficultyProcessor, StrainConstants};
+use vsrg_utils::maps::structures::HitObjectInfo;
+use vsrg_utils::maps::QuaverMap;
+
+// Rows of (start time, lanes), every note a tap.
+fn taps(mode: GameMode, rows: &[(i32, &[i32])]) -> QuaverMap {
+    let hit_objects = rows
+        .iter()
+        .flat_map(|&(start_time, lanes)| {
+            lanes.iter().map(move |&lane| HitObjectInfo {
+                start_time,
+                lane,
+                end_time: 0,
+            })
+        })
+        .collect();
+
+    QuaverMap {
+        mode,
+        hit_objects,
+        ..Default::default()
+    }
+}
+
+// The same rows repeated `count` times, `gap` ms apart.
+fn repeated(mode: GameMode, pattern: &[&'static [i32]], gap: i32, count: usize) -> QuaverMap {
+    let rows: Vec<(i32, &[i32])> = pattern
+        .iter()
+        .cycle()
+        .take(pattern.len() * count)
+        .enumerate()
+        .map(|(i, &lanes)| (1000 + i as i32 * gap, lanes))
+        .collect();
+
+    taps(mode, &rows)
+}
+
+fn coverage(map: &QuaverMap, pattern: QssPatternFlags) -> f32 {
+    let mods = Some(ModIdentifier::empty());
+    DifficultyProcessor::new(map, StrainConstants::new(), mods, Some(true))
+        .pattern_coverage(pattern)
+}
+
+#[test]
+fn minijack() {
+    let map = taps(
+        GameMode::Keys4,
+        &[(1000, &[1]), (1150, &[1]), (2000, &[3]), (3000, &[4])],
+    );
+
+    assert_eq!(coverage(&map, QssPatternFlags::MiniJack), 0.5);
+    assert_eq!(coverage(&map, QssPatternFlags::LongJack), 0.);
+}
+
+#[test]
+fn longjack() {
+    let map = repeated(GameMode::Keys4, &[&[1]], 150, 6);
+
+    assert_eq!(coverage(&map, QssPatternFlags::LongJack), 1.);
+    assert_eq!(coverage(&map, QssPatternFlags::MiniJack), 0.);
+    assert_eq!(coverage(&map, QssPatternFlags::LightStream), 0.);
+}
+
+#[test]
+fn chordjack() {
+    let map = repeated(GameMode::Keys4, &[&[1, 2, 3], &[2, 3, 4]], 250, 4);
+
+    assert_eq!(coverage(&map, QssPatternFlags::ChordJack), 1.);
+    assert_eq!(coverage(&map, QssPatternFlags::KoreaJack), 0.);
+    assert_eq!(coverage(&map, QssPatternFlags::ControlVibro), 0.);
+}
+
+#[test]
+fn korea_jack() {
+    let map = repeated(GameMode::Keys4, &[&[1, 2]], 250, 6);
+
+    assert_eq!(coverage(&map, QssPatternFlags::KoreaJack), 1.);
+    assert_eq!(coverage(&map, QssPatternFlags::ChordJack), 0.);
+}
+
+#[test]
+fn quad_jack() {
+    let map = repeated(GameMode::Keys4, &[&[1, 2, 3, 4]], 250, 4);
+
+    assert_eq!(coverage(&map, QssPatternFlags::QuadJack), 1.);
+    assert_eq!(coverage(&map, QssPatternFlags::QuadStream), 0.);
+}
+
+#[test]
+fn control_vibro() {
+    let map = repeated(GameMode::Keys4, &[&[1, 2]], 100, 8);
+
+    assert_eq!(coverage(&map, QssPatternFlags::ControlVibro), 1.);
+}
+
+#[test]
+fn light_stream() {
+    let map = repeated(GameMode::Keys4, &[&[1], &[2], &[3], &[4]], 100, 4);
+
+    assert_eq!(coverage(&map, QssPatternFlags::LightStream), 1.);
+    assert_eq!(coverage(&map, QssPatternFlags::MiniJack), 0.);
+}
+
+#[test]
+fn jump_stream() {
+    let map = repeated(GameMode::Keys4, &[&[1, 3], &[2], &[4]], 100, 4);
+
+    assert!(coverage(&map, QssPatternFlags::JumpStream) > 0.);
+    assert_eq!(coverage(&map, QssPatternFlags::MiniJack), 0.);
+}
+
+#[test]
+fn hand_stream() {
+    let map = repeated(GameMode::Keys4, &[&[1, 2, 3], &[4]], 100, 4);
+
+    assert!(coverage(&map, QssPatternFlags::HandStream) > 0.);
+    assert_eq!(coverage(&map, QssPatternFlags::ChordJack), 0.);
+}
+
+#[test]
+fn quad_stream() {
+    let map = repeated(
+        GameMode::Keys7,
+        &[&[1, 2, 3, 5], &[6], &[1, 2, 3, 5], &[7]],
+        100,
+        4,
+    );
+
+    assert!(coverage(&map, QssPatternFlags::QuadStream) > 0.);
+    assert_eq!(coverage(&map, QssPatternFlags::QuadJack), 0.);
+}
+
+#[test]
+fn jump_trill() {
+    let map = repeated(GameMode::Keys4, &[&[1, 2], &[3, 4]], 100, 6);
+
+    assert_eq!(coverage(&map, QssPatternFlags::JumpTrill), 1.);
+    assert_eq!(coverage(&map, QssPatternFlags::SplitTrill), 0.);
+    assert_eq!(coverage(&map, QssPatternFlags::KoreaJack), 0.);
+}
+
+#[test]
+fn split_trill() {
+    let map = repeated(GameMode::Keys4, &[&[1, 3], &[2, 4]], 100, 6);
+
+    assert_eq!(coverage(&map, QssPatternFlags::SplitTrill), 1.);
+    assert_eq!(coverage(&map, QssPatternFlags::JumpTrill), 0.);
+}
+
+#[test]
+fn inverse_ln() {
+    let map = QuaverMap {
+        hit_objects: (0..4)
+            .map(|i| HitObjectInfo {
+                start_time: 1000 + i * 500,
+                lane: 1,
+                end_time: 1000 + i * 500 + 400,
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    assert_eq!(coverage(&map, QssPatternFlags::InverseLN), 1.);
+}
+
+#[test]
+fn release_ln() {
+    let map = QuaverMap {
+        hit_objects: vec![
+            HitObjectInfo {
+                start_time: 1000,
+                lane: 1,
+                end_time: 2000,
+            },
+            HitObjectInfo {
+                start_time: 1300,
+                lane: 2,
+                end_time: 1800,
+            },
+        ],
+        ..Default::default()
+    };
+
+    assert_eq!(coverage(&map, QssPatternFlags::ReleaseLN), 0.5);
+}
+
+#[test]
+fn polyrhythm() {
+    let left = (0..8).map(|i| (1000 + i * 150, [1].as_slice()));
+    let right = (0..6).map(|i| (1000 + i * 200 + 50, [4].as_slice()));
+    let mut rows: Vec<(i32, &[i32])> = left.chain(right).collect();
+    rows.sort();
+
+    assert!(coverage(&taps(GameMode::Keys4, &rows), QssPatternFlags::Polyrhythm) > 0.);
+}
@@ -0,0 +1,128 @@
+use vsrg_utils::enums::quaver::ModIdentifier;
+use vsrg_utils::maps::processors::{PlayerRatingProcessor, PlayerScore};
+
+// At 98% accuracy a score is rated exactly the map's difficulty.
+fn score(map_md5: &str, difficulty_rating: f32, accuracy: f32, time: i64) -> PlayerScore {
+    PlayerScore {
+        map_md5: map_md5.to_string(),
+        difficulty_rating,
+        accuracy,
+        mods: ModIdentifier::empty(),
+        failed: false,
+        time,
+    }
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-3,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+fn processor() -> PlayerRatingProcessor {
+    let mut processor = PlayerRatingProcessor::new(vec![
+        score("a", 10., 98., 1),
+        // Rated 10 * (90 / 98)^6 = 6.0, below the first play.
+        score("a", 10., 90., 2),
+        score("b", 20., 98., 3),
+        PlayerScore {
+            failed: true,
+            ..score("c", 5., 98., 4)
+        },
+    ]);
+
+    processor.add_score(score("a", 12., 98., 5));
+    processor.add_score(PlayerScore {
+        mods: ModIdentifier::Autoplay,
+        ..score("d", 30., 98., 6)
+    });
+    processor
+}
+
+#[test]
+fn best_score_per_map() {
+    let processor = processor();
+    let best: Vec<(&str, i64)> = processor
+        .best_scores()
+        .into_iter()
+        .map(|s| (s.map_md5.as_str(), s.time))
+        .collect();
+
+    // Failed and autoplay scores are rated 0 and ordered by time.
+    assert_eq!(best, vec![("b", 3), ("a", 5), ("c", 4), ("d", 6)]);
+    assert_close(processor.scores[1].rating(), 5.999273);
+}
+
+#[test]
+fn overall_rating_and_performance_points() {
+    let processor = processor();
+
+    // 20 + 12 * 0.95.
+    assert_close(processor.overall_rating(), 31.4);
+    // The same plus 416.6667 * (1 - 0.9994^4) for four ranked maps.
+    assert_close(processor.performance_points(), 32.3991);
+}
+
+#[test]
+fn only_the_top_scores_are_weighted() {
+    let scores = |count: i64| {
+        PlayerRatingProcessor::new(
+            (0..count)
+                .map(|i| score(&i.to_string(), 1., 98., i))
+                .collect(),
+        )
+    };
+
+    // (1 - 0.95^50) / 0.05, the 10 scores past the top 50 don't count.
+    let processor = scores(60);
+    assert_close(processor.overall_rating(), 18.4611);
+    // (1 - 0.95^60) / 0.05 + 416.6667 * (1 - 0.9994^60).
+    assert_close(processor.performance_points(), 33.8162);
+
+    // (1 - 0.95^100) / 0.05 + 416.6667 * (1 - 0.9994^120), all maps count for the bonus.
+    let processor = scores(120);
+    assert_close(processor.overall_rating(), 18.4611);
+    assert_close(processor.performance_points(), 48.8354);
+
+    assert_eq!(PlayerRatingProcessor::default().overall_rating(), 0.);
+    assert_eq!(PlayerRatingProcessor::default().performance_points(), 0.);
+}
+
+#[test]
+fn history_follows_play_order() {
+    let mut processor = processor();
+    // Out of order on purpose, history sorts by time.
+    processor.scores.reverse();
+
+    let history: Vec<(i64, f32, f32)> = processor
+        .history()
+        .into_iter()
+        .map(|p| (p.time, p.overall_rating, p.performance_points))
+        .collect();
+
+    let bonus = [0.25, 0.49985, 0.74955, 0.9991];
+    let expected = [
+        (1, 10., 10. + bonus[0]),
+        (2, 10., 10. + bonus[0]),
+        (3, 29.5, 29.5 + bonus[1]),
+        (4, 29.5, 29.5 + bonus[2]),
+        (5, 31.4, 31.4 + bonus[2]),
+        (6, 31.4, 31.4 + bonus[3]),
+    ];
+
+    assert_eq!(history.len(), expected.len());
+    for ((time, overall, pp), (expected_time, expected_overall, expected_pp)) in
+        history.into_iter().zip(expected)
+    {
+        assert_eq!(time, expected_time);
+        assert_close(overall, expected_overall);
+        assert_close(pp, expected_pp);
+    }
+
+    let last = *processor.history().last().unwrap();
+    assert_eq!(last.overall_rating, processor.overall_rating());
+    assert_close(last.performance_points, processor.performance_points());
+}
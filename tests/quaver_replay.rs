@@ -0,0 +1,34 @@
+use vsrg_utils::replays::QuaverReplay;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden");
+
+fn write_and_read(replay: &QuaverReplay, name: &str) -> QuaverReplay {
+    let path = format!("{}/{}", env!("CARGO_TARGET_TMPDIR"), name);
+    replay.to_file(&path).unwrap();
+    QuaverReplay::from_file(&path)
+}
+
+#[test]
+fn randomize_seed_round_trips() {
+    let mut replay = QuaverReplay::from_file(&format!("{}/replays/4k_stream_human.qr", FIXTURES));
+    assert_eq!(replay.replay_version, "0.0.1");
+    assert_eq!(replay.randomize_modifier_seed, 0);
+
+    replay.randomize_modifier_seed = -1_234_567;
+    let read = write_and_read(&replay, "randomize_seed.qr");
+
+    assert_eq!(read.randomize_modifier_seed, -1_234_567);
+    assert_eq!(read.to_bytes().unwrap(), replay.to_bytes().unwrap());
+}
+
+#[test]
+fn unversioned_replays_have_no_randomize_seed() {
+    let mut replay = QuaverReplay::from_file(&format!("{}/replays/4k_stream_human.qr", FIXTURES));
+    replay.replay_version = "None".to_string();
+    replay.randomize_modifier_seed = 42;
+
+    let read = write_and_read(&replay, "unversioned.qr");
+
+    assert_eq!(read.randomize_modifier_seed, 0);
+    assert_eq!(read.frames.len(), replay.frames.len());
+}
@@ -0,0 +1,102 @@
+use vsrg_utils::enums::{osu, quaver};
+use vsrg_utils::replays::osu::{KeyPressState, ReplayEvent};
+use vsrg_utils::replays::{OsuReplay, QuaverReplay};
+
+fn osu_replay(mods: osu::ModIdentifier) -> OsuReplay {
+    OsuReplay {
+        mode: 3,
+        mods,
+        replay_data: vec![
+            ReplayEvent {
+                time_delta: 100,
+                keys: KeyPressState::from_bits_truncate(0b0001),
+            },
+            ReplayEvent {
+                time_delta: 50,
+                keys: KeyPressState::empty(),
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+fn quaver_replay(mods: quaver::ModIdentifier) -> QuaverReplay {
+    QuaverReplay {
+        mods,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn osu_mods_are_mapped_to_quaver() {
+    let conversion = QuaverReplay::from_osu_replay(
+        &osu_replay(
+            osu::ModIdentifier::NoFail
+                | osu::ModIdentifier::DoubleTime
+                | osu::ModIdentifier::Mirror
+                | osu::ModIdentifier::Hidden
+                | osu::ModIdentifier::Key4,
+        ),
+        4,
+    )
+    .unwrap();
+
+    assert_eq!(
+        conversion.replay.mods,
+        quaver::ModIdentifier::NoFail
+            | quaver::ModIdentifier::Speed15X
+            | quaver::ModIdentifier::Mirror
+    );
+    assert_eq!(conversion.unmapped_mods, osu::ModIdentifier::Hidden);
+    assert_eq!(conversion.replay.mode, quaver::GameMode::Keys4 as i32);
+}
+
+#[test]
+fn key_mods_for_another_key_count_are_unmapped() {
+    let conversion =
+        QuaverReplay::from_osu_replay(&osu_replay(osu::ModIdentifier::Key4), 7).unwrap();
+
+    assert_eq!(conversion.replay.mods, quaver::ModIdentifier::empty());
+    assert_eq!(conversion.unmapped_mods, osu::ModIdentifier::Key4);
+    assert_eq!(conversion.replay.mode, quaver::GameMode::Keys7 as i32);
+}
+
+#[test]
+fn unsupported_key_counts_are_rejected() {
+    for key_count in [1, 3, 6, 9] {
+        let error =
+            QuaverReplay::from_osu_replay(&osu_replay(osu::ModIdentifier::empty()), key_count)
+                .err()
+                .unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            format!("Quaver has no game mode for {} keys", key_count)
+        );
+    }
+}
+
+#[test]
+fn quaver_mods_are_mapped_to_osu() {
+    let conversion = OsuReplay::from_quaver_replay(
+        &quaver_replay(
+            quaver::ModIdentifier::NoFail
+                | quaver::ModIdentifier::Speed075X
+                | quaver::ModIdentifier::NoMiss
+                | quaver::ModIdentifier::Speed12X,
+        ),
+        4,
+    );
+
+    assert_eq!(
+        conversion.replay.mods,
+        osu::ModIdentifier::NoFail | osu::ModIdentifier::HalfTime | osu::ModIdentifier::SuddenDeath
+    );
+    assert_eq!(conversion.unmapped_mods, quaver::ModIdentifier::Speed12X);
+
+    // `ModIdentifier::None` is stored as -1 and means no mods at all.
+    let conversion = OsuReplay::from_quaver_replay(&quaver_replay(quaver::ModIdentifier::None), 4);
+
+    assert_eq!(conversion.replay.mods, osu::ModIdentifier::empty());
+    assert_eq!(conversion.unmapped_mods, quaver::ModIdentifier::empty());
+}
@@ -0,0 +1,94 @@
+use vsrg_utils::enums::quaver::LaneLayout;
+use vsrg_utils::replays::quaver::{ReplayFrame, ReplayKeyPressState};
+use vsrg_utils::replays::QuaverReplay;
+
+fn keys(lanes: &[i32]) -> ReplayKeyPressState {
+    lanes
+        .iter()
+        .map(|&lane| ReplayKeyPressState::from_lane(lane).unwrap())
+        .fold(ReplayKeyPressState::empty(), |keys, k| keys | k)
+}
+
+fn replay(frames: &[(i32, &[i32])]) -> QuaverReplay {
+    QuaverReplay {
+        frames: frames
+            .iter()
+            .map(|&(time, lanes)| ReplayFrame {
+                time,
+                keys: keys(lanes),
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn frames(replay: &QuaverReplay) -> Vec<(i32, Vec<i32>)> {
+    replay
+        .frames
+        .iter()
+        .map(|f| (f.time, f.keys.as_lanes()))
+        .collect()
+}
+
+#[test]
+fn remap_lanes_rejects_collisions_without_touching_frames() {
+    let mut edited = replay(&[(-10000, &[]), (100, &[1]), (200, &[2]), (300, &[])]);
+    let before = frames(&edited);
+
+    assert!(edited.remap_lanes(&[2, 2, 3, 4]).is_err());
+    assert_eq!(frames(&edited), before);
+    assert!(!edited.header_stale);
+}
+
+#[test]
+fn remap_lanes_rejects_unmapped_lanes_without_touching_frames() {
+    let mut edited = replay(&[(-10000, &[]), (100, &[1]), (200, &[1, 3]), (300, &[])]);
+    let before = frames(&edited);
+
+    assert!(edited.remap_lanes(&[2, 1]).is_err());
+    assert_eq!(frames(&edited), before);
+    assert!(!edited.header_stale);
+}
+
+#[test]
+fn remap_lanes_moves_every_frame() {
+    let mut edited = replay(&[(-10000, &[]), (100, &[1]), (200, &[1, 3]), (300, &[])]);
+
+    edited.remap_lanes(&[3, 4, 1, 2]).unwrap();
+
+    assert_eq!(
+        frames(&edited),
+        vec![
+            (-10000, vec![]),
+            (100, vec![3]),
+            (200, vec![1, 3]),
+            (300, vec![])
+        ]
+    );
+    assert!(edited.header_stale);
+}
+
+#[test]
+fn mirror_lanes_keeps_the_scratch_lane() {
+    let mut edited = replay(&[(-10000, &[]), (100, &[1, 8]), (200, &[4]), (300, &[7])]);
+
+    edited.mirror_lanes(LaneLayout::Keys7Scratch).unwrap();
+
+    assert_eq!(
+        frames(&edited),
+        vec![
+            (-10000, vec![]),
+            (100, vec![7, 8]),
+            (200, vec![4]),
+            (300, vec![1])
+        ]
+    );
+
+    let mut edited = replay(&[(-10000, &[]), (100, &[1, 5]), (200, &[2])]);
+    edited.mirror_lanes(LaneLayout::Keys4Scratch).unwrap();
+
+    assert_eq!(
+        frames(&edited),
+        vec![(-10000, vec![]), (100, vec![4, 5]), (200, vec![3])]
+    );
+}
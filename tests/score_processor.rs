@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use vsrg_utils::enums::quaver::ModIdentifier;
+use vsrg_utils::maps::processors::{HitStatType, Judgement, KeyPressType, ScoreProcessor};
+use vsrg_utils::maps::structures::HitObjectInfo;
+use vsrg_utils::maps::QuaverMap;
+use vsrg_utils::replays::quaver::{ReplayFrame, ReplayKeyPressState};
+use vsrg_utils::replays::QuaverReplay;
+
+fn map() -> QuaverMap {
+    QuaverMap {
+        hit_objects: vec![
+            HitObjectInfo {
+                start_time: 1000,
+                lane: 1,
+                end_time: 2000,
+            },
+            HitObjectInfo {
+                start_time: 3000,
+                lane: 1,
+                end_time: 0,
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+// Taps 250 ms apart over four lanes.
+fn taps(count: i32) -> QuaverMap {
+    QuaverMap {
+        hit_objects: (0..count)
+            .map(|i| HitObjectInfo {
+                start_time: 1000 + i * 250,
+                lane: i % 4 + 1,
+                end_time: 0,
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn assert_weighting(processor: &mut ScoreProcessor, expected: [f32; 6]) {
+    let expected = HashMap::from([
+        (Judgement::Marv, expected[0]),
+        (Judgement::Perf, expected[1]),
+        (Judgement::Great, expected[2]),
+        (Judgement::Good, expected[3]),
+        (Judgement::Okay, expected[4]),
+        (Judgement::Miss, expected[5]),
+    ]);
+
+    assert_eq!(*processor.judgement_health_weighting(), expected);
+}
+
+#[test]
+fn health_is_weighted_by_note_density() {
+    // 10 actions in 2.25 s, 4.44 per second.
+    let map = taps(10);
+
+    let mut processor = ScoreProcessor::from_map(&map, ModIdentifier::empty(), None);
+    assert_weighting(&mut processor, [1.03, 1., 0.41, -1.1, -1.49, -6.]);
+
+    let mut processor = ScoreProcessor::from_map(&map, ModIdentifier::Autoplay, None);
+    assert_weighting(&mut processor, [0.5, 0.4, 0.2, -3., -4.5, -6.]);
+
+    let mut processor = ScoreProcessor::from_map(&map, ModIdentifier::HeatlthAdjust, None);
+    assert_weighting(&mut processor, [1.03, 1., 0.41, -0.55, -0.745, -3.]);
+}
+
+#[test]
+fn default_health_timeline() {
+    let mut processor = ScoreProcessor::from_map(&taps(10), ModIdentifier::empty(), None);
+
+    let judgements = [
+        Judgement::Miss,
+        Judgement::Miss,
+        Judgement::Good,
+        Judgement::Okay,
+        Judgement::Marv,
+        Judgement::Perf,
+        Judgement::Great,
+    ];
+    for (i, &judgement) in judgements.iter().enumerate() {
+        processor.song_position = 1000 + i as i32 * 250;
+        processor.current_object = Some(i);
+        processor.calculate_score_from_judgement(judgement, None);
+    }
+
+    let expected = [94., 88., 86.9, 85.41, 86.44, 87.44, 87.85];
+    assert_eq!(processor.health_timeline.len(), expected.len());
+
+    for (i, (sample, health)) in processor.health_timeline.iter().zip(expected).enumerate() {
+        assert_eq!(sample.time, 1000 + i as i32 * 250);
+        assert_eq!(sample.object_index, Some(i));
+        assert_eq!(sample.judgement, judgements[i]);
+        assert!(
+            (sample.health - health).abs() < 1e-3,
+            "{}: expected {}, got {}",
+            i,
+            health,
+            sample.health
+        );
+    }
+
+    assert_eq!(processor.health, processor.health_timeline[6].health);
+    assert_eq!(processor.fail_point(), None);
+}
+
+#[test]
+fn overheld_long_note_then_tap_records_each_hit() {
+    let map = map();
+    let lane = ReplayKeyPressState::from_lane(1).unwrap();
+    let replay = QuaverReplay {
+        mods: ModIdentifier::empty(),
+        frames: [
+            (-10000, ReplayKeyPressState::empty()),
+            (1000, lane),
+            (2500, ReplayKeyPressState::empty()),
+            (3010, lane),
+            (3040, ReplayKeyPressState::empty()),
+        ]
+        .into_iter()
+        .map(|(time, keys)| ReplayFrame { time, keys })
+        .collect(),
+        ..Default::default()
+    };
+
+    let processor = ScoreProcessor::from_map_and_replay(&map, &replay, None);
+    let stats: Vec<_> = processor
+        .stats
+        .iter()
+        .map(|s| {
+            (
+                s.key_press_type == KeyPressType::RELEASE,
+                s.type_ == HitStatType::MISS,
+                s.judgement,
+                s.hit_difference,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        stats,
+        vec![
+            (false, false, Judgement::Marv, 0),
+            (true, false, Judgement::Okay, i32::MIN),
+            (false, false, Judgement::Marv, -10),
+        ]
+    );
+}
+
+#[test]
+fn missed_hit_difference_records_a_miss() {
+    let mut processor = ScoreProcessor::from_map(&map(), ModIdentifier::empty(), None);
+
+    let judgement =
+        processor.calculate_score_from_hit_difference(i32::MIN, KeyPressType::PRESS, None);
+
+    assert_eq!(judgement, Judgement::Miss);
+    assert_eq!(processor.current_judgements[&Judgement::Miss], 1);
+    assert_eq!(processor.stats.len(), 1);
+    assert!(processor.stats[0].type_ == HitStatType::MISS);
+    assert_eq!(processor.stats[0].hit_difference, i32::MIN);
+}
+
+#[test]
+fn judging_without_stats_leaves_nothing_behind() {
+    let mut processor = ScoreProcessor::from_map(&map(), ModIdentifier::empty(), None);
+
+    processor.calculate_score_from_hit_difference(i32::MIN, KeyPressType::PRESS, Some(false));
+    processor.calculate_score_from_hit_difference(20, KeyPressType::PRESS, Some(false));
+    assert!(processor.stats.is_empty());
+
+    processor.calculate_score_from_judgement(Judgement::Miss, None);
+
+    assert_eq!(processor.stats.len(), 1);
+    assert_eq!(processor.stats[0].hit_difference, i32::MIN);
+    assert!(processor.stats[0].key_press_type == KeyPressType::NONE);
+}
+
+// Nothing is pressed, so every tap is missed 164 ms after it starts.
+fn missed_taps(mods: ModIdentifier) -> (QuaverMap, QuaverReplay) {
+    let replay = QuaverReplay {
+        mods,
+        frames: vec![ReplayFrame {
+            time: 0,
+            keys: ReplayKeyPressState::empty(),
+        }],
+        ..Default::default()
+    };
+
+    (taps(20), replay)
+}
+
+#[test]
+fn no_fail_play_reports_where_it_would_have_failed() {
+    let (map, replay) = missed_taps(ModIdentifier::NoFail);
+    let processor = ScoreProcessor::from_map_and_replay(&map, &replay, None);
+
+    // Misses drain 6 each, so the 17th takes health from 4 to 0.
+    let fail_point = processor.fail_point().unwrap();
+    assert_eq!(fail_point.time, 1000 + 16 * 250 + 164);
+    assert_eq!(fail_point.object_index, Some(16));
+
+    assert_eq!(processor.health, 0.);
+    assert_eq!(processor.health_timeline.len(), 20);
+    assert_eq!(processor.health_timeline[15].health, 4.);
+    assert!(!processor.failed());
+}
+
+#[test]
+fn what_if_without_no_fail_fails() {
+    let (map, replay) = missed_taps(ModIdentifier::NoFail);
+
+    let without_no_fail = ScoreProcessor::without_no_fail(&map, &replay, None);
+    assert_eq!(without_no_fail.mods, ModIdentifier::empty());
+    assert!(without_no_fail.failed());
+    assert_eq!(
+        without_no_fail.fail_point(),
+        ScoreProcessor::from_map_and_replay(&map, &replay, None).fail_point()
+    );
+
+    let what_if = ScoreProcessor::what_if(&map, &replay, None, ModIdentifier::Mirror);
+    assert!(what_if.failed());
+    assert_eq!(what_if.fail_point().unwrap().object_index, Some(16));
+
+    let what_if = ScoreProcessor::what_if(&map, &replay, None, ModIdentifier::NoFail);
+    assert!(!what_if.failed());
+
+    // Drain is halved, so 20 misses take health down to 40.
+    let what_if = ScoreProcessor::what_if(&map, &replay, None, ModIdentifier::HeatlthAdjust);
+    assert!(!what_if.failed());
+    assert_eq!(what_if.fail_point(), None);
+    assert_eq!(what_if.health, 40.);
+}
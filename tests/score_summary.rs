@@ -0,0 +1,105 @@
+use vsrg_utils::enums::quaver::ModIdentifier;
+use vsrg_utils::maps::processors::{
+    DifficultyProcessor, Grade, Judgement, RatingProcessor, ScoreProcessor, ScoreSummary,
+    StrainConstants,
+};
+use vsrg_utils::maps::QuaverMap;
+use vsrg_utils::replays::QuaverReplay;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden");
+
+#[test]
+fn grade_boundaries() {
+    for (accuracy, grade) in [
+        (100., Grade::X),
+        (99.99, Grade::SS),
+        (99., Grade::SS),
+        (98.99, Grade::S),
+        (95., Grade::S),
+        (94.99, Grade::A),
+        (90., Grade::A),
+        (89.99, Grade::B),
+        (80., Grade::B),
+        (79.99, Grade::C),
+        (70., Grade::C),
+        (69.99, Grade::D),
+        (0., Grade::D),
+    ] {
+        assert_eq!(Grade::from_accuracy(accuracy, false), grade, "{}", accuracy);
+    }
+
+    assert_eq!(Grade::from_accuracy(100., true), Grade::F);
+    assert_eq!(Grade::from_accuracy(95., true), Grade::F);
+}
+
+#[test]
+fn summary_of_a_replay() {
+    let map = QuaverMap::from_path(&format!("{}/maps/4k_chordjack.qua", FIXTURES));
+    let replay = QuaverReplay::from_file(&format!("{}/replays/4k_chordjack_speed.qr", FIXTURES));
+
+    let processor = ScoreProcessor::from_map_and_replay(&map, &replay, None);
+    let difficulty =
+        DifficultyProcessor::new(&map, StrainConstants::new(), Some(replay.mods), None);
+    let rating_processor = RatingProcessor::new(difficulty.overall_difficulty);
+    let summary = ScoreSummary::new(&processor, &rating_processor);
+
+    assert_eq!(summary.score, processor.score);
+    assert_eq!(summary.accuracy, processor.accuracy);
+    assert_eq!(summary.max_combo, processor.max_combo);
+    assert_eq!(summary.judgements, processor.current_judgements);
+    assert_eq!(
+        summary.grade,
+        Grade::from_accuracy(processor.accuracy, false)
+    );
+    assert!(!summary.failed);
+    assert_eq!(summary.mods(), replay.mods);
+    assert_eq!(summary.rate, replay.mods.rate());
+    assert_eq!(
+        summary.rating,
+        rating_processor.calculate_rating(processor.accuracy, Some(false))
+    );
+}
+
+#[test]
+fn summary_round_trip() {
+    let summary = ScoreSummary {
+        player_name: "Player".to_string(),
+        score: 987_654,
+        accuracy: 97.125,
+        max_combo: 812,
+        judgements: [
+            (Judgement::Marv, 700),
+            (Judgement::Perf, 100),
+            (Judgement::Great, 12),
+            (Judgement::Miss, 3),
+        ]
+        .into_iter()
+        .collect(),
+        rating: 31.5,
+        grade: Grade::S,
+        failed: false,
+        mods: (ModIdentifier::Speed12X | ModIdentifier::Mirror).bits(),
+        rate: 1.2,
+    };
+
+    let yaml = summary.to_string().unwrap();
+    assert!(yaml.contains("PlayerName: Player"), "{}", yaml);
+    assert!(yaml.contains("Grade: S"), "{}", yaml);
+    assert_eq!(ScoreSummary::from_string(&yaml).unwrap(), summary);
+    assert_eq!(
+        summary.mods(),
+        ModIdentifier::Speed12X | ModIdentifier::Mirror
+    );
+
+    // Fields missing from the input keep their default value.
+    let partial = ScoreSummary::from_string("Score: 1000\nFailed: true\nGrade: F\n").unwrap();
+    assert_eq!(
+        partial,
+        ScoreSummary {
+            score: 1000,
+            failed: true,
+            grade: Grade::F,
+            ..ScoreSummary::default()
+        }
+    );
+}
@@ -0,0 +1,102 @@
+use vsrg_utils::enums::quaver::ModIdentifier;
+use vsrg_utils::maps::processors::structures::Hand;
+use vsrg_utils::maps::processors::{DifficultyProcessor, StrainConstants};
+use vsrg_utils::maps::QuaverMap;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden");
+const SCRATCH_LANE: i32 = 8;
+
+fn load_map(name: &str) -> QuaverMap {
+    QuaverMap::from_path(&format!("{}/maps/{}", FIXTURES, name))
+}
+
+fn solve(map: &QuaverMap) -> DifficultyProcessor {
+    DifficultyProcessor::new(
+        map,
+        StrainConstants::new(),
+        Some(ModIdentifier::empty()),
+        None,
+    )
+}
+
+#[test]
+fn scratch_notes_are_rated_as_their_own_hand() {
+    let map = load_map("7k_scratch.qua");
+    assert!(map.has_scratch_key);
+    let processor = solve(&map);
+
+    let mut scratch_notes = 0;
+    for data in processor.strain_solver_data.iter() {
+        let on_scratch = data
+            .hit_objects
+            .iter()
+            .filter(|h| h.hit_object.lane == SCRATCH_LANE)
+            .count();
+
+        if data.hand == Hand::Scratch {
+            assert_eq!(on_scratch, data.hit_objects.len());
+        } else {
+            assert_eq!(on_scratch, 0);
+        }
+
+        scratch_notes += on_scratch;
+    }
+
+    // 7K is solved once for each thumb, so every scratch note shows up twice.
+    let expected = map
+        .hit_objects
+        .iter()
+        .filter(|h| h.lane == SCRATCH_LANE)
+        .count();
+    assert!(expected > 0);
+    assert_eq!(scratch_notes, expected * 2);
+
+    let graph = processor.difficulty_graph();
+    assert!(graph.scratch_bins.iter().any(|b| b.strain > 0.));
+}
+
+#[test]
+fn scratch_strain_is_only_set_for_scratch_maps() {
+    let processor = solve(&load_map("7k_scratch.qua"));
+    assert!(processor.scratch_strain > 0.);
+    assert_eq!(
+        processor.scratch_strain,
+        processor.difficulty_graph().scratch_strain
+    );
+
+    for name in [
+        "4k_stream.qua",
+        "4k_chordjack.qua",
+        "4k_ln.qua",
+        "7k_mixed.qua",
+    ] {
+        let processor = solve(&load_map(name));
+
+        assert_eq!(processor.scratch_strain, 0., "{}", name);
+        assert!(
+            processor
+                .difficulty_graph()
+                .scratch_bins
+                .iter()
+                .all(|b| b.strain == 0.),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn scratch_notes_never_lower_the_rating() {
+    let map = load_map("7k_scratch.qua");
+    let without_scratch = QuaverMap {
+        hit_objects: map
+            .hit_objects
+            .iter()
+            .filter(|h| h.lane != SCRATCH_LANE)
+            .copied()
+            .collect(),
+        ..map.clone()
+    };
+
+    assert!(solve(&map).overall_difficulty >= solve(&without_scratch).overall_difficulty);
+}
@@ -0,0 +1,157 @@
+use vsrg_utils::enums::quaver::{GameMode, ModIdentifier};
+use vsrg_utils::maps::processors::difficulty::structures::{
+    DifficultyTable, Skillset, SkillsetRatings,
+};
+use vsrg_utils::maps::processors::{DifficultyProcessor, StrainConstants};
+use vsrg_utils::maps::structures::HitObjectInfo;
+use vsrg_utils::maps::QuaverMap;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden");
+
+fn load_map(name: &str) -> QuaverMap {
+    QuaverMap::from_path(&format!("{}/maps/{}", FIXTURES, name))
+}
+
+// The same rows of notes repeated `count` times, `gap` ms apart, held for `hold` ms if set.
+fn repeated(pattern: &[&[i32]], gap: i32, count: usize, hold: i32) -> QuaverMap {
+    let hit_objects = pattern
+        .iter()
+        .cycle()
+        .take(pattern.len() * count)
+        .enumerate()
+        .flat_map(|(i, lanes)| {
+            let start_time = 1000 + i as i32 * gap;
+            lanes.iter().map(move |&lane| HitObjectInfo {
+                start_time,
+                lane,
+                end_time: if hold > 0 { start_time + hold } else { 0 },
+            })
+        })
+        .collect();
+
+    QuaverMap {
+        mode: GameMode::Keys4,
+        hit_objects,
+        ..Default::default()
+    }
+}
+
+fn skillsets(map: &QuaverMap) -> SkillsetRatings {
+    let mods = Some(ModIdentifier::empty());
+    DifficultyProcessor::new(map, StrainConstants::new(), mods, Some(true)).skillset_ratings
+}
+
+fn hardest(ratings: &SkillsetRatings) -> Skillset {
+    Skillset::ALL
+        .into_iter()
+        .max_by(|a, b| ratings.get(*a).total_cmp(&ratings.get(*b)))
+        .unwrap()
+}
+
+#[test]
+fn jack_maps_rate_highest_in_jack() {
+    let jacks = repeated(
+        &[&[1], &[1], &[1], &[1], &[3], &[3], &[3], &[3]],
+        150,
+        20,
+        0,
+    );
+    assert_eq!(hardest(&skillsets(&jacks)), Skillset::Jack);
+
+    let chordjacks = skillsets(&load_map("4k_chordjack.qua"));
+    assert_eq!(hardest(&chordjacks), Skillset::Chordjack);
+    assert_eq!(chordjacks.stream, 0.);
+}
+
+#[test]
+fn stream_maps_rate_highest_in_stream() {
+    let stream = skillsets(&repeated(&[&[1], &[3], &[2], &[4]], 100, 40, 0));
+    assert_eq!(hardest(&stream), Skillset::Stream);
+    assert_eq!(stream.jack, 0.);
+    assert_eq!(stream.chordjack, 0.);
+
+    assert_eq!(
+        hardest(&skillsets(&load_map("4k_stream.qua"))),
+        Skillset::Stream
+    );
+}
+
+#[test]
+fn long_note_rating_follows_long_notes() {
+    let pattern: &[&[i32]] = &[&[1], &[2], &[3], &[4]];
+    let taps = skillsets(&repeated(pattern, 200, 40, 0));
+    let holds = skillsets(&repeated(pattern, 200, 40, 300));
+
+    assert_eq!(taps.long_note, 0.);
+    assert!(holds.long_note > 0.);
+    assert!(holds.overall > taps.overall);
+
+    // The long note fixture loses its long note rating once every note is a tap.
+    let mut map = load_map("4k_ln.qua");
+    assert!(skillsets(&map).long_note > 0.);
+
+    for hit_object in map.hit_objects.iter_mut() {
+        hit_object.end_time = 0;
+    }
+    assert_eq!(skillsets(&map).long_note, 0.);
+}
+
+#[test]
+fn difficulty_table_rates_skillsets_per_rate() {
+    let map = load_map("4k_stream.qua");
+    let rates = [
+        (0.8, ModIdentifier::Speed08X),
+        (1., ModIdentifier::empty()),
+        (1.5, ModIdentifier::Speed15X),
+    ];
+
+    let table = DifficultyProcessor::difficulty_table(
+        &map,
+        StrainConstants::new(),
+        Some(&rates.map(|(rate, _)| rate)),
+        Some(true),
+    );
+
+    assert_eq!(table.version, DifficultyProcessor::VERSION);
+    assert_eq!(table.rates.len(), rates.len());
+
+    for (rate, mods) in rates {
+        let processor =
+            DifficultyProcessor::new(&map, StrainConstants::new(), Some(mods), Some(true));
+        let row = table.get(rate).unwrap();
+
+        assert_eq!(
+            row.overall_difficulty, processor.overall_difficulty,
+            "{}",
+            rate
+        );
+        assert_eq!(
+            row.skillset_ratings,
+            Some(processor.skillset_ratings),
+            "{}",
+            rate
+        );
+    }
+
+    // Skillsets get harder with the rate.
+    let stream = |rate| table.get(rate).unwrap().skillset_ratings.unwrap().stream;
+    assert!(stream(0.8) < stream(1.) && stream(1.) < stream(1.5));
+
+    let overall_only =
+        DifficultyProcessor::difficulty_table(&map, StrainConstants::new(), Some(&[1.]), None);
+    assert_eq!(overall_only.rates[0].skillset_ratings, None);
+}
+
+#[test]
+fn difficulty_table_round_trip() {
+    let table = DifficultyProcessor::difficulty_table(
+        &load_map("4k_ln.qua"),
+        StrainConstants::new(),
+        Some(&[0.8, 1., 1.5]),
+        Some(true),
+    );
+
+    let yaml = table.to_string().unwrap();
+    assert!(yaml.contains("SkillsetRatings"), "{}", yaml);
+    assert_eq!(DifficultyTable::from_string(&yaml).unwrap(), table);
+}
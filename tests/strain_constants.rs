@@ -0,0 +1,138 @@
+use vsrg_utils::maps::processors::{
+    ConstantsFitter, DifficultyProcessor, FittingDataset, FittingSample, StrainConstants,
+};
+use vsrg_utils::maps::QuaverMap;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden");
+
+fn load_map(name: &str) -> QuaverMap {
+    QuaverMap::from_path(&format!("{}/maps/{}", FIXTURES, name))
+}
+
+#[test]
+fn default_is_the_tuned_profile() {
+    assert_eq!(StrainConstants::default(), StrainConstants::new());
+    assert_eq!(
+        StrainConstants::profile("default"),
+        Some(StrainConstants::new())
+    );
+}
+
+#[test]
+fn bundled_profiles_load_by_name() {
+    let names: Vec<String> = StrainConstants::profiles()
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    assert_eq!(names, vec!["Default", "NoScratchChord"]);
+
+    let no_scratch_chord = StrainConstants::profile("NoScratchChord").unwrap();
+    assert_eq!(
+        no_scratch_chord,
+        StrainConstants {
+            name: "NoScratchChord".to_string(),
+            scratch_chord_multiplier: 1.,
+            ..StrainConstants::new()
+        }
+    );
+
+    assert_eq!(StrainConstants::profile("Missing"), None);
+}
+
+#[test]
+fn profiles_round_trip() {
+    let profiles = vec![
+        StrainConstants::new(),
+        StrainConstants::new()
+            .with_parameter("RollMaxStrainValue", 60.)
+            .unwrap(),
+    ];
+
+    let read = StrainConstants::from_string(&StrainConstants::to_string(&profiles).unwrap());
+    assert_eq!(read.unwrap(), profiles);
+
+    let path = format!("{}/strain_profiles.yaml", env!("CARGO_TARGET_TMPDIR"));
+    StrainConstants::to_file(&profiles, &path).unwrap();
+    assert_eq!(StrainConstants::from_path(&path).unwrap(), profiles);
+
+    // Fields missing from a file keep their default value.
+    let partial = StrainConstants::from_string("- Name: Partial\n  VibroMultiplier: 0.5\n");
+    assert_eq!(
+        partial.unwrap(),
+        vec![StrainConstants {
+            name: "Partial".to_string(),
+            vibro_multiplier: 0.5,
+            ..StrainConstants::new()
+        }]
+    );
+}
+
+#[test]
+fn with_parameter_sets_one_constant() {
+    let constants = StrainConstants::new()
+        .with_parameter("SJackUpperBoundaryMs", 300.)
+        .unwrap();
+
+    assert_eq!(
+        constants,
+        StrainConstants {
+            s_jack_upper_boundary_ms: 300.,
+            ..StrainConstants::new()
+        }
+    );
+    assert!(constants
+        .parameters()
+        .unwrap()
+        .contains(&("SJackUpperBoundaryMs".to_string(), 300.)));
+
+    for name in ["SJackUpperBoundary", "Name"] {
+        let error = StrainConstants::new()
+            .with_parameter(name, 1.)
+            .expect_err("unknown constants should be rejected");
+        assert_eq!(
+            error.to_string(),
+            format!("Unknown strain constant: {}", name)
+        );
+    }
+}
+
+#[test]
+fn fitting_lowers_the_loss() {
+    let map = load_map("4k_stream.qua");
+    let rating =
+        DifficultyProcessor::difficulty_table(&map, StrainConstants::new(), Some(&[1.]), None)
+            .rates[0]
+            .overall_difficulty;
+    let dataset = FittingDataset::new(vec![FittingSample {
+        map,
+        rate: 1.,
+        rating: rating * 1.2,
+    }]);
+
+    let parameters = vec![
+        "BracketMaxStrainValue".to_string(),
+        "RollMaxStrainValue".to_string(),
+    ];
+    let mut fitter = ConstantsFitter::new(dataset.clone(), None, Some(parameters.clone()));
+    fitter.max_iterations = 5;
+    let report = fitter.fit().unwrap();
+
+    assert!(report.initial_loss > 0.);
+    assert!(report.loss < report.initial_loss);
+    assert_eq!(report.loss, dataset.loss(&report.constants));
+    assert_eq!(report.constants.name, "Default (fitted)");
+
+    let mut names: Vec<String> = report
+        .sensitivities
+        .iter()
+        .map(|s| s.name.clone())
+        .collect();
+    names.sort();
+    assert_eq!(names, parameters);
+
+    let unknown = ConstantsFitter::new(dataset, None, Some(vec!["Unknown".to_string()]));
+    assert_eq!(
+        unknown.fit().unwrap_err().to_string(),
+        "Unknown strain constant: Unknown"
+    );
+}
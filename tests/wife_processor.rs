@@ -0,0 +1,167 @@
+use vsrg_utils::enums::quaver::ModIdentifier;
+use vsrg_utils::maps::processors::{KeyPressType, WifeProcessor};
+use vsrg_utils::maps::structures::HitObjectInfo;
+use vsrg_utils::maps::QuaverMap;
+use vsrg_utils::replays::quaver::{ReplayFrame, ReplayKeyPressState};
+use vsrg_utils::replays::QuaverReplay;
+
+// Offsets in ms and the points Etterna's Wife3 gives for them.
+const J4: [(f32, f32); 11] = [
+    (0., 2.),
+    (3., 2.),
+    (10., 1.998777),
+    (22.5, 1.983794),
+    (45., 1.574474),
+    (60., 0.48916),
+    (90., -1.195652),
+    (135., -3.347826),
+    (170., -5.021739),
+    (179., -5.452174),
+    (200., -5.5),
+];
+
+const J7: [(f32, f32); 11] = [
+    (0., 2.),
+    (2., 2.),
+    (10., 1.994631),
+    (22.5, 1.818728),
+    (30., 1.270373),
+    (45., -0.680208),
+    (60., -2.286806),
+    (80., -4.428935),
+    (89., -5.392894),
+    (90., -5.5),
+    (135., -5.5),
+];
+
+// The miss weight is reached at 180 ms times the judge's timing scale.
+const J1: [(f32, f32); 11] = [
+    (0., 2.),
+    (7.5, 2.),
+    (10., 1.999338),
+    (22.5, 1.994866),
+    (45., 1.904844),
+    (90., -0.057413),
+    (135., -1.41806),
+    (180., -2.778707),
+    (269., -5.469763),
+    (270., -5.5),
+    (300., -5.5),
+];
+
+fn long_note_map() -> QuaverMap {
+    QuaverMap {
+        hit_objects: vec![
+            HitObjectInfo {
+                start_time: 1000,
+                lane: 1,
+                end_time: 2000,
+            },
+            HitObjectInfo {
+                start_time: 3000,
+                lane: 1,
+                end_time: 0,
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+fn replay(frames: &[(i32, bool)]) -> QuaverReplay {
+    QuaverReplay {
+        mods: ModIdentifier::empty(),
+        frames: frames
+            .iter()
+            .map(|&(time, pressed)| ReplayFrame {
+                time,
+                keys: if pressed {
+                    ReplayKeyPressState::from_lane(1).unwrap()
+                } else {
+                    ReplayKeyPressState::empty()
+                },
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn wife3_matches_etterna() {
+    for (judge, values) in [(1, J1), (4, J4), (7, J7)] {
+        let timing_scale = WifeProcessor::JUDGE_SCALES[judge - 1];
+
+        for (offset, points) in values {
+            for offset in [offset, -offset] {
+                let actual = WifeProcessor::wife3(offset, timing_scale);
+                assert!(
+                    (actual - points).abs() < 1e-4,
+                    "J{} at {} ms: expected {}, got {}",
+                    judge,
+                    offset,
+                    points,
+                    actual
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn releases_inside_the_scaled_bad_window_are_not_drops() {
+    let map = long_note_map();
+
+    for (judge, bad_window) in [(1, 270), (4, 180), (7, 90)] {
+        let mut processor = WifeProcessor::from_map(&map, ModIdentifier::empty(), None);
+        processor.set_judge(judge);
+
+        assert_eq!(
+            processor.calculate_score_from_hit_difference(bad_window, KeyPressType::RELEASE),
+            0.,
+            "J{}",
+            judge
+        );
+        assert_eq!(processor.hold_drops, 0, "J{}", judge);
+
+        processor.calculate_score_from_hit_difference(bad_window + 1, KeyPressType::RELEASE);
+        assert_eq!(processor.hold_drops, 1, "J{}", judge);
+    }
+}
+
+#[test]
+fn overheld_long_notes_are_not_drops() {
+    let map = long_note_map();
+    let overheld = replay(&[
+        (-10000, false),
+        (1000, true),
+        (2500, false),
+        (3000, true),
+        (3030, false),
+    ]);
+
+    let processor = WifeProcessor::from_map_and_replay(&map, &overheld, None);
+
+    assert_eq!(processor.hold_drops, 0);
+    assert_eq!(processor.misses, 0);
+    assert_eq!(processor.accuracy, 100.);
+}
+
+#[test]
+fn dropped_and_missed_long_notes_are_drops() {
+    let map = long_note_map();
+
+    let dropped = replay(&[
+        (-10000, false),
+        (1000, true),
+        (1300, false),
+        (3000, true),
+        (3030, false),
+    ]);
+    let processor = WifeProcessor::from_map_and_replay(&map, &dropped, None);
+    assert_eq!(processor.hold_drops, 1);
+    assert_eq!(processor.misses, 0);
+
+    let missed = replay(&[(-10000, false), (3000, true), (3030, false)]);
+    let processor = WifeProcessor::from_map_and_replay(&map, &missed, None);
+    assert_eq!(processor.hold_drops, 1);
+    assert_eq!(processor.misses, 1);
+}